@@ -0,0 +1,149 @@
+//! enums.rs
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields, Ident, Variant};
+
+const INTEGERS: [&str; 10] = [
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128",
+];
+
+/// Finds the integer type in the enum's `#[repr(..)]`
+fn repr(input: &DeriveInput) -> syn::Result<Ident> {
+    let mut repr = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                if INTEGERS.contains(&ident.to_string().as_str()) {
+                    repr = Some(ident.clone());
+                }
+            }
+            Ok(())
+        })?;
+    }
+    repr.ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "ChompEnum requires an integer repr, e.g. #[repr(u8)]",
+        )
+    })
+}
+
+/// Whether a variant is marked `#[chomp(other)]`
+fn is_other(variant: &Variant) -> syn::Result<bool> {
+    let mut other = false;
+    for attr in variant.attrs.iter().filter(|a| a.path().is_ident("chomp")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("other") {
+                return Err(meta.error("unknown chomp attribute"));
+            }
+            other = true;
+            Ok(())
+        })?;
+    }
+    Ok(other)
+}
+
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "ChompEnum can only be derived for enums",
+        ));
+    };
+    let repr = repr(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // discriminants are spelled out as constants so they can be matched against
+    let mut consts = Vec::new();
+    let mut read_arms = Vec::new();
+    let mut write_arms = Vec::new();
+    let mut previous = None;
+    let mut other = None;
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        if is_other(variant)? {
+            if other.is_some() {
+                return Err(Error::new_spanned(
+                    variant,
+                    "only one variant can be #[chomp(other)]",
+                ));
+            }
+            if !matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1) {
+                return Err(Error::new_spanned(
+                    variant,
+                    "the #[chomp(other)] variant must have a single field holding the repr",
+                ));
+            }
+            other = Some(ident);
+            continue;
+        }
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "ChompEnum variants can't have fields, except the #[chomp(other)] variant",
+            ));
+        }
+        let discriminant = match (&variant.discriminant, &previous) {
+            (Some((_, expr)), _) => quote!(#expr),
+            (None, Some(previous)) => quote!(#previous + 1),
+            (None, None) => quote!(0),
+        };
+        let konst = format_ident!("__CHOMP_{}", ident);
+        consts.push(quote!(const #konst: #repr = #discriminant;));
+        read_arms.push(quote!(#konst => #name::#ident,));
+        write_arms.push(quote!(#name::#ident => #konst,));
+        previous = Some(konst);
+    }
+
+    // unknown discriminants either fall back to the other variant or error
+    let unknown = match other {
+        Some(other) => {
+            write_arms.push(quote!(#name::#other(value) => *value,));
+            quote!(#name::#other(value))
+        }
+        None => quote! {
+            return Err(::bitchomp::TryFromBytesError::InvalidDiscriminant {
+                value: value as i128,
+                type_name: ::std::any::type_name::<Self>(),
+            })
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::bitchomp::TryFromBytes for #name #ty_generics #where_clause {
+            type Bytes = ::std::vec::Vec<u8>;
+            type Error = ::bitchomp::TryFromBytesError;
+
+            const SIZE: usize = <#repr as ::bitchomp::TryFromBytes>::SIZE;
+
+            #[allow(non_upper_case_globals)]
+            fn try_from_bytes(
+                bytes: Self::Bytes,
+                endianness: ::bitchomp::Endianness,
+            ) -> ::std::result::Result<(Self, usize), Self::Error> {
+                #(#consts)*
+                let (value, size) =
+                    <#repr as ::bitchomp::TryFromBytes>::try_from_bytes(bytes, endianness)?;
+                let value = match value {
+                    #(#read_arms)*
+                    _ => #unknown,
+                };
+                Ok((value, size))
+            }
+        }
+
+        impl #impl_generics ::bitchomp::ToBytes for #name #ty_generics #where_clause {
+            type Bytes = ::std::vec::Vec<u8>;
+
+            #[allow(non_upper_case_globals)]
+            fn to_bytes(&self, endianness: ::bitchomp::Endianness) -> Self::Bytes {
+                #(#consts)*
+                let value: #repr = match self {
+                    #(#write_arms)*
+                };
+                ::bitchomp::ToBytes::to_bytes(&value, endianness)
+            }
+        }
+    })
+}
@@ -0,0 +1,56 @@
+//! Derive macros for bitchomp
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod enums;
+mod structs;
+
+/// Derives TryFromBytes and ToBytes for a field-less enum with an integer repr
+///
+/// The discriminant is read and written as the repr type in the reader's or writer's
+/// endianness, and unknown discriminants fail with `TryFromBytesError::InvalidDiscriminant`.
+///
+/// To accept unknown discriminants instead, mark one tuple variant holding the repr with
+/// `#[chomp(other)]`. It captures the raw value on read and writes it back unchanged.
+#[proc_macro_derive(ChompEnum, attributes(chomp))]
+pub fn derive_chomp_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    enums::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives TryFromBytes for a struct whose fields all implement TryFromBytes
+///
+/// Fields are read in declaration order in the reader's endianness, except for fields
+/// marked `#[chomp(endian = "big")]` or `#[chomp(endian = "little")]`, which are always
+/// read in that endianness along with any fields nested in them. A field which fails to
+/// convert is reported as `TryFromBytesError::Field` with its name and offset.
+///
+/// Integer and bool fields marked `#[bits(N)]` are packed, with each run of them taking
+/// up a whole number of bytes. Bits are read most significant first unless the struct is
+/// marked `#[chomp(bit_order = "lsb_first")]`.
+///
+/// `#[pad(N)]` skips N bytes before a field and `#[align(N)]` skips up to the next
+/// multiple of N bytes from the start of the struct. `#[pad(N, strict)]` errors with
+/// `TryFromBytesError::NonZeroPadding` if any of the skipped bytes aren't zero.
+#[proc_macro_derive(TryFromBytes, attributes(bits, chomp, pad, align))]
+pub fn derive_try_from_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    structs::derive_try_from_bytes(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives ToBytes for a struct whose fields all implement ToBytes
+///
+/// Fields are written in declaration order in the writer's endianness, with
+/// `#[chomp(endian = "..")]` and `#[bits(N)]` fields handled as for the TryFromBytes
+/// derive. `#[pad(N)]` and `#[align(N)]` write zeros.
+#[proc_macro_derive(ToBytes, attributes(bits, chomp, pad, align))]
+pub fn derive_to_bytes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    structs::derive_to_bytes(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,435 @@
+//! structs.rs
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::ParseStream, Data, DeriveInput, Error, Field, Ident, Index, LitInt, LitStr, Member,
+    Token, Type,
+};
+
+/// A field of the struct being derived
+struct StructField<'a> {
+    member: Member,
+    /// The local the field is read into
+    local: Ident,
+    name: String,
+    ty: &'a Type,
+    /// The endianness from `#[chomp(endian = "..")]`, overriding the reader's or writer's
+    endian: Option<TokenStream>,
+}
+
+/// A run of fields laid out in the buffer
+enum Segment<'a> {
+    /// A field read and written through its own TryFromBytes and ToBytes
+    Field(StructField<'a>),
+    /// Consecutive `#[bits(N)]` fields packed into whole bytes
+    Packed(Vec<(StructField<'a>, u32)>),
+    /// `#[pad(N)]` bytes before the field `name`, which must be zero if `strict`
+    Pad {
+        len: usize,
+        strict: bool,
+        name: String,
+    },
+    /// `#[align(N)]` padding before the field `name` up to a multiple of `align` bytes
+    Align { align: usize, name: String },
+}
+
+/// The layout of the struct being derived
+struct Layout<'a> {
+    segments: Vec<Segment<'a>>,
+    /// The BitOrder of packed fields
+    bit_order: TokenStream,
+}
+
+impl<'a> Layout<'a> {
+    fn new(input: &'a DeriveInput, derive: &str) -> syn::Result<Self> {
+        let Data::Struct(data) = &input.data else {
+            return Err(Error::new_spanned(
+                &input.ident,
+                format!("{derive} can only be derived for structs"),
+            ));
+        };
+
+        let mut segments = Vec::new();
+        for (i, field) in data.fields.iter().enumerate() {
+            let field_bits = bits(field)?;
+            let spacers = spacers(field)?;
+            let field = StructField::new(i, field)?;
+            if let (Some(_), Some(endian)) = (field_bits, &field.endian) {
+                return Err(Error::new_spanned(
+                    endian,
+                    "#[bits(N)] fields are packed the same in either endianness",
+                ));
+            }
+            // padding before a packed field starts a new group
+            for spacer in spacers {
+                segments.push(match spacer {
+                    Spacer::Pad(len, strict) => Segment::Pad {
+                        len,
+                        strict,
+                        name: field.name.clone(),
+                    },
+                    Spacer::Align(align) => Segment::Align {
+                        align,
+                        name: field.name.clone(),
+                    },
+                });
+            }
+            match (field_bits, segments.last_mut()) {
+                (Some(n), Some(Segment::Packed(group))) => group.push((field, n)),
+                (Some(n), _) => segments.push(Segment::Packed(vec![(field, n)])),
+                (None, _) => segments.push(Segment::Field(field)),
+            }
+        }
+        for segment in &segments {
+            if let Segment::Packed(group) = segment {
+                let total: u32 = group.iter().map(|(_, n)| n).sum();
+                if !total.is_multiple_of(8) {
+                    return Err(Error::new_spanned(
+                        group[0].0.ty,
+                        format!("packed fields are {total} bits wide, which isn't a whole number of bytes"),
+                    ));
+                }
+            }
+        }
+
+        Ok(Layout {
+            segments,
+            bit_order: bit_order(input)?,
+        })
+    }
+
+    /// Returns an expression for the number of bytes the struct occupies
+    fn size(&self) -> TokenStream {
+        let steps = self.segments.iter().map(|segment| match segment {
+            Segment::Field(field) => {
+                let ty = field.ty;
+                quote!(size += <#ty as ::bitchomp::TryFromBytes>::SIZE;)
+            }
+            Segment::Packed(group) => {
+                let len = packed_len(group);
+                quote!(size += #len;)
+            }
+            Segment::Pad { len, .. } => quote!(size += #len;),
+            Segment::Align { align, .. } => quote!(size = size.next_multiple_of(#align);),
+        });
+        quote! {{
+            let mut size = 0usize;
+            #(#steps)*
+            size
+        }}
+    }
+
+    /// Returns an expression for whether any field is variable size
+    fn variable_size(&self) -> TokenStream {
+        let fields = self.segments.iter().filter_map(|segment| match segment {
+            Segment::Field(field) => {
+                let ty = field.ty;
+                Some(quote!(<#ty as ::bitchomp::TryFromBytes>::VARIABLE_SIZE))
+            }
+            _ => None,
+        });
+        quote!(false #(|| #fields)*)
+    }
+
+    /// Returns every field in declaration order
+    fn fields(&self) -> Vec<&StructField<'a>> {
+        let mut fields = Vec::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Field(field) => fields.push(field),
+                Segment::Packed(group) => fields.extend(group.iter().map(|(field, _)| field)),
+                Segment::Pad { .. } | Segment::Align { .. } => {}
+            }
+        }
+        fields
+    }
+}
+
+impl<'a> StructField<'a> {
+    fn new(index: usize, field: &'a Field) -> syn::Result<Self> {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+        let name = match &member {
+            Member::Named(ident) => ident.to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        };
+        Ok(StructField {
+            member,
+            local: format_ident!("__field{}", index),
+            name,
+            ty: &field.ty,
+            endian: endian(field)?,
+        })
+    }
+
+    /// Returns an expression for the endianness the field is read and written in
+    fn endianness(&self) -> TokenStream {
+        self.endian.clone().unwrap_or_else(|| quote!(endianness))
+    }
+
+    fn is_bool(&self) -> bool {
+        matches!(self.ty, Type::Path(path) if path.path.is_ident("bool"))
+    }
+}
+
+/// Parses a field's `#[bits(N)]` attribute
+fn bits(field: &Field) -> syn::Result<Option<u32>> {
+    let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("bits")) else {
+        return Ok(None);
+    };
+    let n: u32 = attr.parse_args::<LitInt>()?.base10_parse()?;
+    if n == 0 || n > 64 {
+        return Err(Error::new_spanned(attr, "bits must be between 1 and 64"));
+    }
+    Ok(Some(n))
+}
+
+/// Parses a field's `#[chomp(endian = "..")]` attribute
+fn endian(field: &Field) -> syn::Result<Option<TokenStream>> {
+    let mut endian = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("chomp")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("endian") {
+                return Err(meta.error("unknown chomp attribute"));
+            }
+            let value: LitStr = meta.value()?.parse()?;
+            endian = Some(match value.value().as_str() {
+                "big" => quote!(::bitchomp::Endianness::Big),
+                "little" => quote!(::bitchomp::Endianness::Little),
+                _ => {
+                    return Err(Error::new_spanned(
+                        value,
+                        "endian must be \"big\" or \"little\"",
+                    ))
+                }
+            });
+            Ok(())
+        })?;
+    }
+    Ok(endian)
+}
+
+/// Padding before a field
+enum Spacer {
+    /// `#[pad(N)]` or `#[pad(N, strict)]`
+    Pad(usize, bool),
+    /// `#[align(N)]`
+    Align(usize),
+}
+
+/// Parses a field's `#[pad(..)]` and `#[align(..)]` attributes in the order they're written
+fn spacers(field: &Field) -> syn::Result<Vec<Spacer>> {
+    let mut spacers = Vec::new();
+    for attr in &field.attrs {
+        if attr.path().is_ident("pad") {
+            let (len, strict) = attr.parse_args_with(|input: ParseStream| {
+                let len = input.parse::<LitInt>()?.base10_parse()?;
+                if input.parse::<Option<Token![,]>>()?.is_none() {
+                    return Ok((len, false));
+                }
+                let ident: Ident = input.parse()?;
+                if ident != "strict" {
+                    return Err(Error::new_spanned(ident, "expected `strict`"));
+                }
+                Ok((len, true))
+            })?;
+            spacers.push(Spacer::Pad(len, strict));
+        } else if attr.path().is_ident("align") {
+            let align: usize = attr.parse_args::<LitInt>()?.base10_parse()?;
+            if !align.is_power_of_two() {
+                return Err(Error::new_spanned(attr, "align must be a power of two"));
+            }
+            spacers.push(Spacer::Align(align));
+        }
+    }
+    Ok(spacers)
+}
+
+/// Parses the struct's `#[chomp(bit_order = "..")]` attribute, defaulting to MsbFirst
+fn bit_order(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let mut order = quote!(::bitchomp::BitOrder::MsbFirst);
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("chomp")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("bit_order") {
+                return Err(meta.error("unknown chomp attribute"));
+            }
+            let value: LitStr = meta.value()?.parse()?;
+            order = match value.value().as_str() {
+                "msb_first" => quote!(::bitchomp::BitOrder::MsbFirst),
+                "lsb_first" => quote!(::bitchomp::BitOrder::LsbFirst),
+                _ => {
+                    return Err(Error::new_spanned(
+                        value,
+                        "bit_order must be \"msb_first\" or \"lsb_first\"",
+                    ))
+                }
+            };
+            Ok(())
+        })?;
+    }
+    Ok(order)
+}
+
+/// Returns the number of bytes a group of packed fields occupies
+fn packed_len(group: &[(StructField, u32)]) -> usize {
+    group.iter().map(|(_, n)| *n as usize).sum::<usize>() / 8
+}
+
+pub fn derive_try_from_bytes(input: DeriveInput) -> syn::Result<TokenStream> {
+    let layout = Layout::new(&input, "TryFromBytes")?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let size = layout.size();
+    let variable_size = layout.variable_size();
+    let bit_order = &layout.bit_order;
+
+    let reads = layout.segments.iter().map(|segment| match segment {
+        Segment::Field(field) => {
+            let StructField {
+                local, name, ty, ..
+            } = field;
+            let endianness = field.endianness();
+            quote! {
+                let (#local, size) = <#ty as ::bitchomp::TryFromBytes>::try_from_bytes(
+                    bytes[offset.min(bytes.len())..].into(),
+                    #endianness,
+                )
+                .map_err(|error| ::bitchomp::TryFromBytesError::Field {
+                    name: #name,
+                    offset,
+                    error: ::std::boxed::Box::new(::bitchomp::TryFromBytesError::custom(error)),
+                })?;
+                offset += size;
+            }
+        }
+        Segment::Packed(group) => {
+            let len = packed_len(group);
+            let first = &group[0].0.name;
+            let fields = group.iter().map(|(field, n)| {
+                let StructField { local, ty, .. } = field;
+                if field.is_bool() {
+                    quote!(let #local = bits.read_bits(#n).expect("the packed bytes are in bounds") != 0;)
+                } else {
+                    quote! {
+                        const _: () = assert!(#n <= <#ty>::BITS, "too many bits for the field's type");
+                        let #local = bits.read_bits(#n).expect("the packed bytes are in bounds") as #ty;
+                    }
+                }
+            });
+            quote! {
+                let packed = bytes.get(offset..offset + #len).ok_or(
+                    ::bitchomp::TryFromBytesError::Field {
+                        name: #first,
+                        offset,
+                        error: ::std::boxed::Box::new(::bitchomp::TryFromBytesError::OutOfBounds),
+                    },
+                )?;
+                let mut bits = ::bitchomp::BitReader::from_slice(packed).with_bit_order(#bit_order);
+                #(#fields)*
+                offset += #len;
+            }
+        }
+        Segment::Pad { len, strict, name } => {
+            let check = strict.then(|| {
+                quote! {
+                    if let Some(i) = padding.iter().position(|&byte| byte != 0) {
+                        return Err(::bitchomp::TryFromBytesError::NonZeroPadding {
+                            offset: offset + i,
+                            byte: padding[i],
+                        });
+                    }
+                }
+            });
+            quote! {
+                let padding = bytes.get(offset..offset + #len).ok_or(
+                    ::bitchomp::TryFromBytesError::Field {
+                        name: #name,
+                        offset,
+                        error: ::std::boxed::Box::new(::bitchomp::TryFromBytesError::OutOfBounds),
+                    },
+                )?;
+                #check
+                offset += #len;
+            }
+        }
+        Segment::Align { align, name } => quote! {
+            if offset.next_multiple_of(#align) > bytes.len() {
+                return Err(::bitchomp::TryFromBytesError::Field {
+                    name: #name,
+                    offset,
+                    error: ::std::boxed::Box::new(::bitchomp::TryFromBytesError::OutOfBounds),
+                });
+            }
+            offset = offset.next_multiple_of(#align);
+        },
+    });
+    let fields = layout.fields();
+    let members = fields.iter().map(|field| &field.member);
+    let locals = fields.iter().map(|field| &field.local);
+
+    Ok(quote! {
+        impl #impl_generics ::bitchomp::TryFromBytes for #name #ty_generics
+        #where_clause
+        {
+            type Bytes = ::std::vec::Vec<u8>;
+            type Error = ::bitchomp::TryFromBytesError;
+
+            const SIZE: usize = #size;
+            const VARIABLE_SIZE: bool = #variable_size;
+
+            fn try_from_bytes(
+                bytes: Self::Bytes,
+                endianness: ::bitchomp::Endianness,
+            ) -> ::std::result::Result<(Self, usize), Self::Error> {
+                let mut offset = 0usize;
+                #(#reads)*
+                Ok((Self { #(#members: #locals),* }, offset))
+            }
+        }
+    })
+}
+
+pub fn derive_to_bytes(input: DeriveInput) -> syn::Result<TokenStream> {
+    let layout = Layout::new(&input, "ToBytes")?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let bit_order = &layout.bit_order;
+
+    let writes = layout.segments.iter().map(|segment| match segment {
+        Segment::Field(field) => {
+            let StructField { member, ty, .. } = field;
+            let endianness = field.endianness();
+            quote! {
+                bytes.extend(<#ty as ::bitchomp::ToBytes>::to_bytes(&self.#member, #endianness));
+            }
+        }
+        Segment::Packed(group) => {
+            let fields = group.iter().map(
+                |(StructField { member, .. }, n)| quote!(bits.write_bits(self.#member as u64, #n);),
+            );
+            quote! {
+                let mut bits = ::bitchomp::BitWriter::new().with_bit_order(#bit_order);
+                #(#fields)*
+                bytes.extend(bits.into_bytes());
+            }
+        }
+        Segment::Pad { len, .. } => quote!(bytes.resize(bytes.len() + #len, 0);),
+        Segment::Align { align, .. } => {
+            quote!(bytes.resize(bytes.len().next_multiple_of(#align), 0);)
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::bitchomp::ToBytes for #name #ty_generics #where_clause {
+            type Bytes = ::std::vec::Vec<u8>;
+
+            fn to_bytes(&self, endianness: ::bitchomp::Endianness) -> Self::Bytes {
+                let mut bytes = ::std::vec::Vec::new();
+                #(#writes)*
+                bytes
+            }
+        }
+    })
+}
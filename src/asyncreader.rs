@@ -0,0 +1,147 @@
+//! asyncreader.rs
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::{streaming::StreamBuffer, ByteReaderResource, Endianness, Error, ErrorKind};
+
+/// A tool for reading bytes from a tokio AsyncRead source, like StreamingByteReader
+///
+/// Positions in errors are offsets in the stream, and the error kinds are the same as
+/// the sync readers'.
+///
+/// # Cancel safety
+///
+/// The read methods are cancel safe: bytes only leave the internal buffer once a whole
+/// value has been decoded, so a cancelled read leaves them to be read again by the next
+/// call. A cancelled `skip_bytes` may have skipped part of the way, as told by `position`.
+pub struct AsyncByteReader<R> {
+    source: R,
+    buf: StreamBuffer,
+}
+
+impl<R: AsyncRead + Unpin> AsyncByteReader<R> {
+    /// Returns an AsyncByteReader reading from `source`
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{AsyncByteReader, Endianness, Error};
+    ///
+    /// async fn read_header(
+    ///     socket: impl tokio::io::AsyncRead + Unpin,
+    /// ) -> Result<u32, Error> {
+    ///     let mut reader = AsyncByteReader::new(socket, Endianness::Little);
+    ///     Ok(reader.read::<u32>().await?)
+    /// }
+    /// ```
+    pub fn new(source: R, endianness: Endianness) -> Self {
+        AsyncByteReader {
+            source,
+            buf: StreamBuffer::new(endianness),
+        }
+    }
+
+    /// Sets the number of bytes read from the source at a time, which is 8192 by default
+    pub fn with_chunk_size(mut self, chunk: usize) -> Self {
+        self.buf.chunk = chunk.max(1);
+        self
+    }
+
+    /// Sets the most bytes `read_sized_vector` will buffer for one vector, 64 MiB by
+    /// default
+    pub fn with_max_buffer(mut self, max_buffer: usize) -> Self {
+        self.buf.max_buffer = max_buffer;
+        self
+    }
+
+    /// Returns the position in the stream
+    pub fn position(&self) -> usize {
+        self.buf.position()
+    }
+
+    /// Reads more from the source, setting `eof` at the end of the stream
+    async fn fill_more(&mut self) -> Result<(), Error> {
+        self.buf.compact();
+        self.buf.buf.reserve(self.buf.chunk);
+        // read_buf only appends what was read, so cancelling it can't leave junk behind
+        let read = self
+            .source
+            .read_buf(&mut self.buf.buf)
+            .await
+            .map_err(|e| self.buf.err(ErrorKind::Io(e)))?;
+        self.buf.eof = read == 0;
+        Ok(())
+    }
+
+    /// Reads a type T from the stream
+    ///
+    /// Errors with `UnexpectedEof` if the stream ends partway through the value.
+    pub async fn read<T: for<'a> ByteReaderResource<'a>>(&mut self) -> Result<T, Error> {
+        Ok(self.read_n(1).await?.remove(0))
+    }
+
+    /// Reads a type T from the stream n times
+    ///
+    /// Errors with `UnexpectedEof` if the stream ends partway through the values, without
+    /// consuming any of them.
+    pub async fn read_n<T: for<'a> ByteReaderResource<'a>>(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<T>, Error> {
+        loop {
+            if let Some(values) = self.buf.try_read_n(n)? {
+                return Ok(values);
+            }
+            self.fill_more().await?;
+        }
+    }
+
+    /// Reads a NUL-terminated UTF-8 string from the stream
+    ///
+    /// Errors with `UnterminatedString` if the stream ends before a NUL byte.
+    pub async fn read_string(&mut self) -> Result<String, Error> {
+        let mut scanned = 0;
+        loop {
+            if let Some(value) = self.buf.try_read_string(&mut scanned)? {
+                return Ok(value);
+            }
+            self.fill_more().await?;
+        }
+    }
+
+    /// Reads a vector of T prefixed by its u32 length, like `ByteReader::read_sized_vector`
+    ///
+    /// Errors with `DeclaredSizeTooLarge` if the vector would take more than the
+    /// `with_max_buffer` limit, without reading it from the source.
+    pub async fn read_sized_vector<T: for<'a> ByteReaderResource<'a>>(
+        &mut self,
+    ) -> Result<Vec<T>, Error> {
+        let mut want = 0;
+        loop {
+            if let Some(values) = self.buf.try_read_sized_vector(&mut want)? {
+                return Ok(values);
+            }
+            self.fill_more().await?;
+        }
+    }
+
+    /// Skips `n` bytes of the stream
+    ///
+    /// Errors with `UnexpectedEof` if the stream ends first, having skipped to its end.
+    pub async fn skip_bytes(&mut self, n: usize) -> Result<(), Error> {
+        let start = self.buf.position();
+        let mut left = n;
+        loop {
+            let buffered = left.min(self.buf.window().len());
+            self.buf.consume(buffered);
+            left -= buffered;
+            if left == 0 {
+                return Ok(());
+            }
+            self.fill_more().await?;
+            if self.buf.eof {
+                return Err(StreamBuffer::eof_err::<[u8]>(start, n, n - left));
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! bcd.rs
+use std::io::BufRead;
+
+use super::{ByteReader, ByteWriter, Error, ErrorKind, WriteErrorKind};
+
+impl<'a> ByteReader<'a> {
+    /// Reads `len` bytes of packed BCD, two digits per byte with the most significant first
+    ///
+    /// Errors with `InvalidBcd` on a nibble above 9 and `BcdOverflow` if the value doesn't
+    /// fit in a u64, leaving the cursor untouched.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x23, 0x45];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_bcd(3)?, 12345);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_bcd(&mut self, len: usize) -> Result<u64, Error> {
+        let digits = self.bcd_digits(len)?;
+        let value = digits
+            .iter()
+            .try_fold(0u64, |value, &digit| {
+                value.checked_mul(10)?.checked_add(digit as u64)
+            })
+            .ok_or_else(|| self.err(ErrorKind::BcdOverflow))?;
+        self.consume(len);
+        Ok(value)
+    }
+
+    /// Reads `len` bytes of packed BCD as a string of digits, keeping leading zeros
+    ///
+    /// Errors in the same way as `read_bcd`, but never overflows.
+    pub fn read_bcd_string(&mut self, len: usize) -> Result<String, Error> {
+        let value = self
+            .bcd_digits(len)?
+            .iter()
+            .map(|&digit| (b'0' + digit) as char)
+            .collect();
+        self.consume(len);
+        Ok(value)
+    }
+
+    /// Returns the digits of the next `len` bytes of packed BCD without consuming them
+    fn bcd_digits(&self, len: usize) -> Result<Vec<u8>, Error> {
+        if self.len() < len {
+            return Err(self.eof_err::<[u8]>(len, self.len()));
+        }
+        let mut digits = Vec::with_capacity(len * 2);
+        for (i, &byte) in self.cursor[..len].iter().enumerate() {
+            for nibble in [byte >> 4, byte & 0x0F] {
+                if nibble > 9 {
+                    return Err(self.err(ErrorKind::InvalidBcd {
+                        offset: self.absolute_cursor() + i,
+                        nibble,
+                    }));
+                }
+                digits.push(nibble);
+            }
+        }
+        Ok(digits)
+    }
+}
+
+impl ByteWriter {
+    /// Appends `value` as `len` bytes of packed BCD, returning the bytes written
+    ///
+    /// Errors if `value` has more than `2 * len` digits.
+    pub fn write_bcd(&mut self, value: u64, len: usize) -> Result<usize, Error> {
+        let mut bytes = vec![0u8; len];
+        let mut rest = value;
+        for byte in bytes.iter_mut().rev() {
+            *byte = (rest % 10) as u8 | (((rest / 10) % 10) as u8) << 4;
+            rest /= 100;
+        }
+        if rest != 0 {
+            return Err(self.err(WriteErrorKind::BcdOverflow { value, len }));
+        }
+        self.append_vec(bytes);
+        Ok(len)
+    }
+}
@@ -0,0 +1,132 @@
+//! bitreader.rs
+use std::{borrow::BorrowMut, marker::PhantomData};
+
+use super::{ByteReader, Endianness, Error, ErrorKind};
+
+/// The order in which bits are read out of each byte
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum BitOrder {
+    /// The most significant bit of each byte is read first
+    #[default]
+    MsbFirst,
+    /// The least significant bit of each byte is read first
+    LsbFirst,
+}
+
+/// A tool for reading values which don't land on byte boundaries
+///
+/// Bytes are taken from the underlying ByteReader as soon as any of their bits are
+/// read, so after `align_to_byte` its cursor is just past the last byte touched.
+pub struct BitReader<'a, R: BorrowMut<ByteReader<'a>> = ByteReader<'a>> {
+    reader: R,
+    /// The byte currently being read
+    current: u8,
+    /// The number of bits of `current` already read, 8 when there is no current byte
+    used: u32,
+    order: BitOrder,
+    lifetime: PhantomData<&'a [u8]>,
+}
+
+impl<'a> BitReader<'a> {
+    /// Returns a BitReader reading from buf
+    pub fn from_slice(buf: &'a [u8]) -> Self {
+        BitReader::new(ByteReader::new(buf, Endianness::default()))
+    }
+}
+
+impl<'a, R: BorrowMut<ByteReader<'a>>> BitReader<'a, R> {
+    /// Returns a BitReader reading from a ByteReader, or a mutable reference to one
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{BitReader, ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0b1010_0000, 0x2A];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let mut bits = BitReader::new(&mut reader);
+    ///     assert_eq!(bits.read_bits(3)?, 0b101);
+    ///     bits.align_to_byte();
+    ///
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0x2A);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn new(reader: R) -> Self {
+        BitReader {
+            reader,
+            current: 0,
+            used: 8,
+            order: BitOrder::default(),
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Sets the order in which bits are read out of each byte
+    pub fn with_bit_order(mut self, order: BitOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Returns the position of the next bit relative to the start of the buffer
+    pub fn bit_position(&self) -> usize {
+        self.reader.borrow().cursor() * 8 - (8 - self.used as usize)
+    }
+
+    /// Reads a single bit
+    pub fn read_bit(&mut self) -> Result<bool, Error> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    /// Reads `n` bits, with the first bit read being the most significant for
+    /// `BitOrder::MsbFirst` and the least significant for `BitOrder::LsbFirst`
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - the number of bits to read, at most 64
+    pub fn read_bits(&mut self, n: u32) -> Result<u64, Error> {
+        assert!(n <= 64, "cannot read more than 64 bits at once");
+        let available = (8 - self.used) as usize + self.reader.borrow().len() * 8;
+        if available < n as usize {
+            let reader = self.reader.borrow();
+            return Err(reader.err(ErrorKind::NoBits {
+                bit: self.bit_position(),
+            }));
+        }
+
+        let mut value = 0u64;
+        for i in 0..n {
+            if self.used == 8 {
+                self.current = self.reader.borrow_mut().read::<u8>()?.inner();
+                self.used = 0;
+            }
+            let bit = match self.order {
+                BitOrder::MsbFirst => (self.current >> (7 - self.used)) & 1,
+                BitOrder::LsbFirst => (self.current >> self.used) & 1,
+            } as u64;
+            self.used += 1;
+            value = match self.order {
+                BitOrder::MsbFirst => (value << 1) | bit,
+                BitOrder::LsbFirst => value | (bit << i),
+            };
+        }
+        Ok(value)
+    }
+
+    /// Skips the rest of the current byte, returning how many bits were skipped
+    pub fn align_to_byte(&mut self) -> u32 {
+        let skipped = 8 - self.used;
+        self.used = 8;
+        skipped
+    }
+
+    /// Aligns to the next byte and returns the underlying reader
+    pub fn into_inner(mut self) -> R {
+        self.align_to_byte();
+        self.reader
+    }
+}
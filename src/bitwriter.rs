@@ -0,0 +1,98 @@
+//! bitwriter.rs
+use super::BitOrder;
+
+/// A tool for writing values which don't land on byte boundaries
+///
+/// The mirror of BitReader. Any bits left in the last byte are zero.
+pub struct BitWriter {
+    buf: Vec<u8>,
+    /// The number of bits of the last byte already written, 8 when there is no partial byte
+    used: u32,
+    order: BitOrder,
+}
+
+impl Default for BitWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitWriter {
+    /// Returns an empty BitWriter
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::BitWriter;
+    ///
+    /// let mut bits = BitWriter::new();
+    /// bits.write_bits(0b101, 3);
+    /// bits.write_bit(true);
+    ///
+    /// assert_eq!(bits.into_bytes(), vec![0b1011_0000]);
+    /// ```
+    pub fn new() -> Self {
+        BitWriter {
+            buf: Vec::new(),
+            used: 8,
+            order: BitOrder::default(),
+        }
+    }
+
+    /// Sets the order in which bits are written into each byte
+    pub fn with_bit_order(mut self, order: BitOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Returns the number of bits written
+    pub fn bit_position(&self) -> usize {
+        self.buf.len() * 8 - (8 - self.used as usize)
+    }
+
+    /// Writes a single bit
+    pub fn write_bit(&mut self, bit: bool) {
+        self.write_bits(bit as u64, 1);
+    }
+
+    /// Writes the low `n` bits of `value`, most significant first for `BitOrder::MsbFirst`
+    /// and least significant first for `BitOrder::LsbFirst`
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - the bits to write, any above the low `n` are ignored
+    /// * `n` - the number of bits to write, at most 64
+    pub fn write_bits(&mut self, value: u64, n: u32) {
+        assert!(n <= 64, "cannot write more than 64 bits at once");
+        for i in 0..n {
+            if self.used == 8 {
+                self.buf.push(0);
+                self.used = 0;
+            }
+            let bit = match self.order {
+                BitOrder::MsbFirst => (value >> (n - 1 - i)) & 1,
+                BitOrder::LsbFirst => (value >> i) & 1,
+            } as u8;
+            let last = self.buf.last_mut().expect("a byte was just pushed");
+            *last |= match self.order {
+                BitOrder::MsbFirst => bit << (7 - self.used),
+                BitOrder::LsbFirst => bit << self.used,
+            };
+            self.used += 1;
+        }
+    }
+
+    /// Pads the current byte with zeros, returning how many bits were skipped
+    pub fn align_to_byte(&mut self) -> u32 {
+        let skipped = 8 - self.used;
+        self.used = 8;
+        skipped
+    }
+
+    /// Aligns to the next byte and returns the bytes written
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        self.align_to_byte();
+        self.buf
+    }
+}
@@ -1,92 +1,120 @@
 //! bytereader.rs
-use core::fmt;
 use std::{
     cmp,
-    convert::Infallible,
     fmt::Debug,
-    io::{self, BufRead, Write},
+    io::{self, BufRead, SeekFrom},
     marker::PhantomData,
-    mem::size_of,
 };
 
-use crate::Chomp;
+use crate::{Chomp, ChompResource, ChompSlice};
 
-use super::{Endianness, TryFromBytes, TryFromBytesError};
+use super::{error::Snippet, Endianness, Error, ErrorKind, ToBytes, TryFromBytesError};
+
+// bytes captured either side of the cursor by errors with snippets
+const SNIPPET_RADIUS: usize = 16;
 
 // resource should have the same lifetime as the bytes.
+// its error can be anything boxable, so types can fail with their own errors
 pub trait ByteReaderResource<'a> =
-    TryFromBytes<Bytes: From<&'a [u8]>, Error = TryFromBytesError> + Clone;
-/// Error returned by ByteReader
-pub struct ByteReaderError {
-    kind: ByteReaderErrorKind,
-    cursor: usize,
-}
-
-impl std::fmt::Debug for ByteReaderError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ByteReaderError")
-            .field("kind", &self.kind)
-            .field_with("cursor", |f| write!(f, "{:#x}", &self.cursor))
-            .finish()
-    }
-}
-#[derive(Debug)]
-pub enum ByteReaderErrorKind {
-    NoBytes,
-    TryFromBytesError(TryFromBytesError),
-    IOError(io::Error),
-    Infallible,
-}
-
-impl From<std::io::Error> for ByteReaderErrorKind {
-    fn from(err: std::io::Error) -> Self {
-        ByteReaderErrorKind::IOError(err)
-    }
-}
-
-impl From<TryFromBytesError> for ByteReaderErrorKind {
-    fn from(err: TryFromBytesError) -> Self {
-        ByteReaderErrorKind::TryFromBytesError(err)
-    }
-}
+    ChompResource<Error: Into<Box<dyn std::error::Error + Send + Sync>>> + Clone + 'a;
 
-impl From<Infallible> for ByteReaderErrorKind {
-    fn from(_: Infallible) -> Self {
-        ByteReaderErrorKind::Infallible
+impl Error {
+    /// Returns a hex dump of `reader`'s buffer around the position of the error
+    ///
+    /// See `ByteReader::context` for the format.
+    pub fn context(&self, reader: &ByteReader) -> String {
+        let pos = match self.position {
+            Some(position) => cmp::min(position.saturating_sub(reader.origin), reader.buf.len()),
+            None => reader.buf.len() - reader.cursor.len(),
+        };
+        hex_dump(reader.buf, reader.origin, pos, 16, 16)
     }
 }
 
-impl From<Infallible> for ByteReaderError {
-    fn from(_: Infallible) -> Self {
-        ByteReaderError {
-            kind: ByteReaderErrorKind::Infallible,
-            cursor: 20, // OK ??
-        }
-    }
+/// A saved cursor position, returned by `ByteReader::mark`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mark {
+    /// The position in the original buffer, so marks survive a rebase
+    position: usize,
 }
 
 /// A tool for reading bytes from a buffer
 #[derive(Clone)]
 pub struct ByteReader<'a> {
     /// A reference to the data that is being read.
-    buf: &'a [u8],
+    pub(crate) buf: &'a [u8],
     /// The current place in the buffer.
-    pub cursor: &'a [u8],
+    pub(crate) cursor: &'a [u8],
     /// The endianness in which the bytes should be read as.
-    endianness: Endianness,
+    pub(crate) endianness: Endianness,
+    /// The position of `buf` in the original buffer, for readers over part of it
+    pub(crate) origin: usize,
+    /// Positions saved by `push_pos`, most recent last
+    positions: Vec<Mark>,
+    /// Whether errors capture the bytes around the cursor
+    error_snippets: bool,
 }
 
 impl<'a> ByteReader<'a> {
-    /// Returns a ByteReaderError with the context of the ByteReader
+    /// Returns an Error with the context of the ByteReader
+    ///
+    /// The error's position is the absolute cursor, so it locates the bytes in the original
+    /// buffer even after a rebase.
     ///
     /// # Arguments
     ///
     /// * `kind` - the kind of error to receive
-    pub fn err(&self, kind: ByteReaderErrorKind) -> ByteReaderError {
-        ByteReaderError {
-            kind,
-            cursor: self.cursor(),
+    pub fn err(&self, kind: ErrorKind) -> Error {
+        let mut error = Error::new(kind, Some(self.absolute_cursor()));
+        if self.error_snippets {
+            let pos = self.cursor();
+            let start = pos.saturating_sub(SNIPPET_RADIUS);
+            let end = cmp::min(pos.saturating_add(SNIPPET_RADIUS), self.buf.len());
+            error.details_mut().snippet = Some(Snippet {
+                bytes: self.buf[start..end].to_vec(),
+                start: self.origin + start,
+            });
         }
+        error
+    }
+
+    /// Returns an `UnexpectedEof` error for a T needing `requested` bytes when only
+    /// `available` remain
+    pub(crate) fn eof_err<T: ?Sized>(&self, requested: usize, available: usize) -> Error {
+        self.err(ErrorKind::UnexpectedEof {
+            requested,
+            available,
+            type_name: std::any::type_name::<T>(),
+        })
+    }
+
+    /// Sets whether errors capture the bytes around the cursor, returning the reader
+    ///
+    /// Errors then display a hex dump of up to 16 bytes either side of where they occurred,
+    /// for bug reports. This is off by default, as it copies the bytes into every error.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness};
+    ///
+    /// let buf = *b"RIFF\x24\x00\x00\x00WAVE";
+    /// let mut reader = ByteReader::new(&buf, Endianness::Little).with_error_snippets(true);
+    /// reader.seek(8).unwrap();
+    ///
+    /// let err = reader.expect_magic(b"AVI ").unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string().lines().skip(1).collect::<Vec<_>>(),
+    ///     [
+    ///         "00000000  52 49 46 46 24 00 00 00 57 41 56 45              |RIFF$...WAVE    |",
+    ///         "                                  ^^",
+    ///     ]
+    /// );
+    /// ```
+    pub fn with_error_snippets(mut self, enabled: bool) -> Self {
+        self.error_snippets = enabled;
+        self
     }
 
     /// Returns a ByteReader reading from buf
@@ -100,9 +128,9 @@ impl<'a> ByteReader<'a> {
     /// ```
     /// #![feature(generic_const_exprs)]
     ///
-    /// use bitchomp::{ByteReader, Endianness, ByteError};
+    /// use bitchomp::{ByteReader, Endianness, Error};
     ///
-    /// fn main() -> Result<(), ByteError> {
+    /// fn main() -> Result<(), Error> {
     ///     // get buffer from file
     ///     let buf = std::fs::read("test/binary.file")?;
     ///     let mut reader = ByteReader::new(&buf, Endianness::default());
@@ -119,18 +147,42 @@ impl<'a> ByteReader<'a> {
             buf,
             cursor: buf,
             endianness,
+            origin: 0,
+            positions: Vec::new(),
+            error_snippets: false,
+        }
+    }
+
+    /// Returns a ByteReader over `buf` that reports positions as if it started at `origin`
+    pub(crate) fn with_origin(buf: &'a [u8], endianness: Endianness, origin: usize) -> Self {
+        ByteReader {
+            origin,
+            ..ByteReader::new(buf, endianness)
         }
     }
 
+    /// Returns a reader over the `len` bytes `start` bytes after the cursor, with its own cursor
+    fn section(&self, start: usize, len: usize) -> ByteReader<'a> {
+        ByteReader::with_origin(
+            &self.cursor[start..start + len],
+            self.endianness,
+            self.absolute_cursor() + start,
+        )
+        .with_error_snippets(self.error_snippets)
+    }
+
     /// Returns a ByteReaderIterator<T> that iterates over a buffer, returing bytes of type T
     ///
+    /// The iterator stops at the first value that can't be read, so a truncated buffer
+    /// looks the same as one that ended cleanly. Use `iter_results` to tell them apart.
+    ///
     /// # Examples
     /// ```
     /// #![feature(generic_const_exprs)]
     ///
-    /// use bitchomp::{ByteReader, Endianness, ByteError};
+    /// use bitchomp::{ByteReader, Endianness, Error};
     /// // get buffer from file
-    /// fn main() -> Result<(), ByteError> {
+    /// fn main() -> Result<(), Error> {
     ///     let buf = std::fs::read("test/values.file")?;
     ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
     ///     let values_squared: Vec<u16> = reader.iter::<u16>().map(|t|t*t).collect();
@@ -140,28 +192,230 @@ impl<'a> ByteReader<'a> {
     ///     Ok(())
     /// }
     /// ```
-    pub fn iter<T: TryFromBytes>(&'a mut self) -> ByteReaderIterator<T>
-    where
-        T::Bytes: TryFrom<Vec<u8>>,
-    {
+    pub fn iter<T: ByteReaderResource<'a>>(&mut self) -> ByteReaderIterator<'_, 'a, T> {
         ByteReaderIterator::<T> {
             buf: self,
             resource_type: PhantomData,
         }
     }
 
+    /// Returns an iterator over values of type T that yields an error, and then stops, at
+    /// the first value that can't be read
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness};
+    ///
+    /// // a u16 table missing its last byte
+    /// let buf = [0x01, 0x00, 0x02, 0x00, 0x03];
+    /// let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    /// let values: Result<Vec<u16>, _> = reader.iter_results().collect();
+    /// assert!(values.is_err());
+    /// ```
+    pub fn iter_results<T: ByteReaderResource<'a>>(
+        &mut self,
+    ) -> ByteReaderResultIterator<'_, 'a, T> {
+        ByteReaderResultIterator::<T> {
+            buf: self,
+            done: false,
+            resource_type: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over groups of `chunk_len` values of type T
+    ///
+    /// A trailing partial chunk yields an error rather than being dropped, after which the
+    /// iterator stops. A `chunk_len` of 0 yields no chunks.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let rows = reader.chunks::<u8>(3).collect::<Result<Vec<_>, _>>()?;
+    ///     assert_eq!(rows, [[0x01, 0x02, 0x03], [0x04, 0x05, 0x06]]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn chunks<T: ByteReaderResource<'a>>(
+        &mut self,
+        chunk_len: usize,
+    ) -> ByteReaderChunks<'_, 'a, T> {
+        ByteReaderChunks::<T> {
+            buf: self,
+            chunk_len,
+            done: chunk_len == 0,
+            resource_type: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over arrays of N values of type T, like `chunks`
+    pub fn chunks_const<T: ByteReaderResource<'a>, const N: usize>(
+        &mut self,
+    ) -> impl Iterator<Item = Result<[T; N], Error>> + use<'_, 'a, T, N> {
+        self.chunks::<T>(N).map(|chunk| {
+            chunk.map(|values| match values.try_into() {
+                Ok(array) => array,
+                Err(_) => unreachable!("chunks are always N long"),
+            })
+        })
+    }
+
     /// Returns the length of the remaining buffer
     pub fn len(&self) -> usize {
         self.cursor.len()
     }
 
+    /// Returns true if there are no bytes left to read
+    pub fn is_empty(&self) -> bool {
+        self.cursor.is_empty()
+    }
+
+    /// Returns the endianness values are read in
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Sets the endianness values are read in from the next read on
+    ///
+    /// Chomps already read keep the endianness they were read in, and readers split off
+    /// with `sub_reader` or similar keep the endianness they were created with.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     // a TIFF header declares its own byte order
+    ///     let buf = *b"MM\x00\x2A";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     if reader.read::<[u8; 2]>()?.inner() == *b"MM" {
+    ///         reader.set_endianness(Endianness::Big);
+    ///     }
+    ///     assert_eq!(reader.read::<u16>()?.inner(), 42);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    /// Returns the unread bytes
+    pub fn remaining(&self) -> &'a [u8] {
+        self.cursor
+    }
+
+    /// Returns the unread bytes and advances the cursor to the end of the buffer
+    pub fn take_remaining(&mut self) -> &'a [u8] {
+        let remaining = self.cursor;
+        self.consume(remaining.len());
+        remaining
+    }
+
+    /// Returns the bytes before the cursor
+    pub fn consumed(&self) -> &'a [u8] {
+        &self.buf[..self.cursor()]
+    }
+
+    /// Returns a hex and ASCII dump from `before` bytes before the cursor to `after` bytes
+    /// after it, for debugging
+    ///
+    /// Rows of 16 bytes are labelled with their offset in the original buffer, and the
+    /// byte at the cursor is marked with `^^` on the line below. The window stops at the
+    /// edges of the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"RIFF\x24\x00\x00\x00WAVE";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///     reader.seek(8)?;
+    ///
+    ///     if let Err(e) = reader.expect_magic(b"AVI ") {
+    ///         eprintln!("{e:?}\n{}", e.context(&reader));
+    ///     }
+    ///     println!("{}", reader.context(4, 4));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn context(&self, before: usize, after: usize) -> String {
+        hex_dump(self.buf, self.origin, self.cursor(), before, after)
+    }
+
     /// Returns the cursor position
     pub fn cursor(&self) -> usize {
         self.buf.len() - self.cursor.len()
     }
 
+    /// Returns the position of the start of this reader's buffer in the buffer it was
+    /// split from, which is 0 unless it has been rebased or came from `sub_reader` or similar
+    pub fn origin(&self) -> usize {
+        self.origin
+    }
+
+    /// Returns the cursor position in the buffer this reader was split from
+    pub fn absolute_cursor(&self) -> usize {
+        self.origin + self.cursor()
+    }
+
+    /// Returns a reader over the next `len` bytes and advances past them
+    ///
+    /// The child reader has its own cursor starting at 0 and can't read past the section.
+    /// Errors with `UnexpectedEof` without moving if fewer than `len` bytes remain.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x02, 0x03, 0x04];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     reader.read::<u8>()?;
+    ///     let mut section = reader.sub_reader(2)?;
+    ///     assert_eq!(section.read::<u16>()?.inner(), 0x0302);
+    ///     assert!(section.read::<u8>().is_err());
+    ///     assert_eq!(section.absolute_cursor(), 3);
+    ///
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0x04);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sub_reader(&mut self, len: usize) -> Result<ByteReader<'a>, Error> {
+        if self.len() < len {
+            return Err(self.eof_err::<[u8]>(len, self.len()));
+        }
+        let section = self.section(0, len);
+        self.consume(len);
+        Ok(section)
+    }
+
     /// Seeks to a position in the buffer
     ///
+    /// Seeking to the end of the buffer is allowed and leaves no bytes to read, while
+    /// seeking past it errors with `SeekOutOfRange` and leaves the cursor untouched.
+    ///
     /// # Arguments
     ///
     /// * `pos` - the position in the buffer
@@ -170,9 +424,9 @@ impl<'a> ByteReader<'a> {
     /// ```
     /// #![feature(generic_const_exprs)]
     ///
-    /// use bitchomp::{ByteError, ByteReader, Endianness};
+    /// use bitchomp::{ByteReader, Endianness, Error};
     ///
-    /// fn main() -> Result<(), ByteError> {
+    /// fn main() -> Result<(), Error> {
     ///     let buf = std::fs::read("test/binary.file")?;
     ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
     ///     if reader.seek(15).is_ok() {
@@ -184,248 +438,2021 @@ impl<'a> ByteReader<'a> {
     ///     Ok(())
     /// }
     /// ```
-    pub fn seek(&mut self, pos: usize) -> Result<(), ByteReaderError> {
+    pub fn seek(&mut self, pos: usize) -> Result<(), Error> {
         if pos > self.buf.len() {
-            return Err(self.err(ByteReaderErrorKind::NoBytes));
+            return Err(self.err(ErrorKind::SeekOutOfRange {
+                position: pos as i128,
+                len: self.buf.len(),
+            }));
         }
         self.cursor = &self.buf[pos..];
         Ok(())
     }
 
-    /// Reads a type T from the buffer
-    ///
-    /// # Arguments
-    ///
-    /// * `T: FromBytes` - the type you want to read
+    /// Saves the cursor position so it can be returned to with `reset`
     ///
     /// # Examples
     /// ```
     /// #![feature(generic_const_exprs)]
     ///
-    /// use bitchomp::{ByteError, ByteReader, Endianness};
+    /// use bitchomp::{ByteReader, Endianness, Error};
     ///
-    /// fn main() -> Result<(), ByteError>{
-    ///     // get buffer
-    ///     let buf = std::fs::read("test/binary.file")?;
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x02, 0x03];
     ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
     ///
-    ///     let value = reader.read::<u32>()?.inner();
-    ///     // only reads 2 bytes!
-    ///     let next_value = reader.read::<u16>()?.inner() as u32;
-    ///     
-    ///     // do stuff...
-    ///     
+    ///     let mark = reader.mark();
+    ///     if reader.read::<u16>()?.inner() != 0xBEEF {
+    ///         reader.reset(&mark)?;
+    ///     }
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0x01);
+    ///
     ///     Ok(())
     /// }
     /// ```
-    pub fn read<T: ByteReaderResource<'a>>(&mut self) -> Result<Chomp<T>, ByteReaderError> {
-        Ok(self.read_n(1)?[0].clone())
+    pub fn mark(&self) -> Mark {
+        Mark {
+            position: self.absolute_cursor(),
+        }
     }
 
-    /// Reads a type T from the buffer
+    /// Returns the cursor to a position saved with `mark`
     ///
-    /// # Arguments
+    /// Errors with `StaleMark` if the reader has since been rebased past the mark.
+    pub fn reset(&mut self, mark: &Mark) -> Result<(), Error> {
+        match mark.position.checked_sub(self.origin) {
+            Some(pos) if pos <= self.buf.len() => {
+                self.cursor = &self.buf[pos..];
+                Ok(())
+            }
+            _ => Err(self.err(ErrorKind::StaleMark {
+                position: mark.position,
+                origin: self.origin,
+            })),
+        }
+    }
+
+    /// Saves the cursor position on a stack, to be restored by `pop_pos`
+    pub fn push_pos(&mut self) {
+        let mark = self.mark();
+        self.positions.push(mark);
+    }
+
+    /// Restores the most recently pushed cursor position
     ///
-    /// * `T: FromBytes` - the type you want to read
+    /// Errors with `EmptyPositionStack` if nothing has been pushed, or `StaleMark` if
+    /// the reader has since been rebased past it.
+    pub fn pop_pos(&mut self) -> Result<(), Error> {
+        let mark = self
+            .positions
+            .pop()
+            .ok_or_else(|| self.err(ErrorKind::EmptyPositionStack))?;
+        self.reset(&mark)
+    }
+
+    /// Seeks to `pos`, runs `f`, then returns to the current position even if `f` errors
     ///
     /// # Examples
     /// ```
     /// #![feature(generic_const_exprs)]
     ///
-    /// use bitchomp::{ByteError, ByteReader, Endianness};
+    /// use bitchomp::{ByteReader, Endianness, Error};
     ///
-    /// fn main() -> Result<(), ByteError>{
-    ///     // get buffer
-    ///     let buf = std::fs::read("test/binary.file")?;
-    ///         
+    /// fn main() -> Result<(), Error> {
+    ///     // a name offset followed by a value, with the name stored later on
+    ///     let buf = *b"\x04\x2Aab\x03foo";
     ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
-    ///     
-    ///     // doesn't consume the next 4 bytes!
-    ///     let full_value = reader.peek::<u32>()?.inner();
     ///
-    ///     // does consume the next 4 bytes!
-    ///     let [first_half, second_half] = [reader.read::<u16>()?.inner() as u32, reader.read::<u16>()?.inner() as u32];
-    ///     
-    ///     // do stuff...
-    ///  
+    ///     let offset = reader.read::<u8>()?.inner() as usize;
+    ///     let name = reader.with_pos(offset, |r| {
+    ///         let len = r.read::<u8>()?.inner() as usize;
+    ///         r.read_string_fixed(len)
+    ///     })?;
+    ///     assert_eq!(name, "foo");
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0x2A);
+    ///
     ///     Ok(())
     /// }
     /// ```
-    pub fn peek<T: ByteReaderResource<'a>>(&mut self) -> Result<Chomp<T>, ByteReaderError> {
-        Ok(self.peek_n(1)?[0].clone())
-    }
-
-    /// Gets the size of the buffer
-    pub fn size(&self) -> usize {
-        self.buf.len()
+    pub fn with_pos<T, E: From<Error>>(
+        &mut self,
+        pos: usize,
+        f: impl FnOnce(&mut ByteReader<'a>) -> Result<T, E>,
+    ) -> Result<T, E> {
+        // kept apart from the position stack, so a push in `f` without a pop can't move it
+        let mark = self.mark();
+        self.seek(pos)?;
+        let result = f(self);
+        self.reset(&mark)?;
+        result
     }
 
-    // std::mem::size_of<T> for T: ByteReaderResource + Size
-    pub fn byte_size<T: ByteReaderResource<'a>>(&mut self) -> Result<usize, ByteReaderError> {
-        let (_, s) = T::try_from_bytes(self.cursor.into(), self.endianness)
-            .map_err(|e| self.err(ByteReaderErrorKind::TryFromBytesError(e)))?;
-        Ok(s)
+    /// Runs `f` on the reader, labelling any error it returns with `label`
+    ///
+    /// See `Error::with_context`. Scopes can be nested, and the label is only formatted if
+    /// `f` fails.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"MATL\x02\x00\x01\x00\x02";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let err = reader
+    ///         .with_context("section 'MATL'", |r| {
+    ///             r.expect_magic(b"MATL")?;
+    ///             let count = r.read_value::<u16>()?;
+    ///             (0..count)
+    ///                 .map(|i| r.with_context(format_args!("entry {i}"), |r| r.read_value::<u16>()))
+    ///                 .collect::<Result<Vec<_>, _>>()
+    ///         })
+    ///         .unwrap_err();
+    ///     assert_eq!(
+    ///         err.to_string(),
+    ///         "while reading section 'MATL' entry 1: u16 needs 2 bytes but only 1 remain at 0x8"
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_context<T>(
+        &mut self,
+        label: impl std::fmt::Display,
+        f: impl FnOnce(&mut ByteReader<'a>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        f(self).map_err(|e| e.with_context(label))
     }
 
-    /// Reads a type T from the buffer
+    /// Runs `f` on the reader, restoring the cursor if it returns an error
     ///
-    /// # Arguments
+    /// The result of `f` is returned unchanged, and transactions can be nested.
     ///
-    /// * `T: FromBytes` - the type you want to read
-    /// * `n: usize` - the number of T to read
     /// # Examples
     /// ```
     /// #![feature(generic_const_exprs)]
     ///
-    /// use bitchomp::{ByteError, ByteReader, Endianness, ChompFlatten};
-    ///
-    /// fn main() -> Result<(), ByteError> {
+    /// use bitchomp::{ByteReader, Endianness, Error};
     ///
-    ///     // get buffer
-    ///     let buf = std::fs::read("test/binary.file")?;
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x02, 0x03];
     ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
     ///
-    ///     // read 32 bytes (or 16 u16s)
-    ///     let values = reader.read_n::<u16>(16)?.flatten();
-    ///     
-    ///     // do stuff ...
+    ///     // a trailing record that isn't all there
+    ///     let trailer = reader.transaction(|r| {
+    ///         let tag = r.read::<u8>()?.inner();
+    ///         let value = r.read::<u32>()?.inner();
+    ///         Ok::<_, Error>((tag, value))
+    ///     });
+    ///     assert!(trailer.is_err());
+    ///     assert_eq!(reader.cursor(), 0);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub fn read_n<T: ByteReaderResource<'a> + Sized>(
+    pub fn transaction<T, E>(
         &mut self,
-        n: usize,
-    ) -> Result<Vec<Chomp<T>>, ByteReaderError> {
-        // handle the error here to avoid consuming bytes we don't have
-        let res = self.peek_n::<T>(n)?;
-        self.consume(n * std::mem::size_of::<T>());
-        Ok(res)
+        f: impl FnOnce(&mut ByteReader<'a>) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let saved = self.clone();
+        let result = f(self);
+        if result.is_err() {
+            *self = saved;
+        }
+        result
     }
-    /// Reads a type T from the buffer n times without consuming
-    ///
-    /// # Arguments
+
+    /// Seeks relative to the start, end or current position, returning the new position
     ///
-    /// * `T: FromBytes` - the type you want to read
-    /// * `n` - the number of T to read
+    /// Errors with `SeekOutOfRange` if the position would be before the start or past the
+    /// end of the buffer, leaving the cursor untouched.
     ///
     /// # Examples
     /// ```
     /// #![feature(generic_const_exprs)]
     ///
-    /// use bitchomp::{ByteReader, ByteError, Endianness, ChompFlatten};
+    /// use std::io::SeekFrom;
     ///
-    /// fn main() -> Result<(), ByteError> {
-    ///     // get buffer
-    ///     let buf = std::fs::read("test/binary.file")?;
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x00, 0x01, 0x02, 0x03, 0x04];
     ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
     ///
-    ///     // read 32 bytes (or 16 u16s)
-    ///     let values = reader.read_n::<u16>(16)?.flatten();
+    ///     // read a footer
+    ///     reader.seek_from(SeekFrom::End(-1))?;
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0x04);
     ///
-    ///     // ... do stuff
+    ///     reader.seek_from(SeekFrom::Start(1))?;
+    ///     reader.seek_from(SeekFrom::Current(2))?;
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0x03);
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub fn peek_n<T: ByteReaderResource<'a> + Sized>(
-        &self,
-        n: usize,
-    ) -> Result<Vec<Chomp<T>>, ByteReaderError> {
-        if self.len() / size_of::<T>() < n {
-            return Err(ByteReaderError {
-                kind: ByteReaderErrorKind::NoBytes,
-                cursor: self.cursor(),
-            });
+    pub fn seek_from(&mut self, pos: SeekFrom) -> Result<usize, Error> {
+        let position = match pos {
+            SeekFrom::Start(offset) => offset as i128,
+            SeekFrom::Current(offset) => self.cursor() as i128 + offset as i128,
+            SeekFrom::End(offset) => self.buf.len() as i128 + offset as i128,
+        };
+        if position < 0 || position > self.buf.len() as i128 {
+            return Err(self.err(ErrorKind::SeekOutOfRange {
+                position,
+                len: self.buf.len(),
+            }));
         }
-        Ok(unsafe {
-            std::mem::transmute::<&[u8], &[T]>(&self.cursor)
-                .iter()
-                .map(|t| Chomp::new(t))
-                .take(n)
-                .collect::<Vec<Chomp<T>>>()
-        })
-    }
-
-    pub fn read_string(&mut self) -> Result<String, ByteReaderError> {
-        let (value, size) = String::try_from_bytes(self.cursor.into(), self.endianness)
-            .map_err(|e| self.err(ByteReaderErrorKind::TryFromBytesError(e)))?;
-        self.consume(size);
-        Ok(value)
+        self.cursor = &self.buf[position as usize..];
+        Ok(position as usize)
     }
 
-    /// Reads a type T from the buffer n times without consuming
+    /// Reads a type T from the buffer
     ///
     /// # Arguments
     ///
     /// * `T: FromBytes` - the type you want to read
-    /// * `n` - the number of T to read
     ///
     /// # Examples
     /// ```
     /// #![feature(generic_const_exprs)]
     ///
-    /// use bitchomp::{ByteReader, ByteError, Endianness, ChompFlatten};
+    /// use bitchomp::{ByteReader, Endianness, Error};
     ///
-    /// fn main() -> Result<(), ByteError> {
+    /// fn main() -> Result<(), Error>{
     ///     // get buffer
     ///     let buf = std::fs::read("test/binary.file")?;
     ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
     ///
-    ///     // read 32 bytes (or 16 u16s)
-    ///     let values = reader.read_n::<u16>(16)?.flatten();
-    ///
-    ///     // ... do stuff
+    ///     let value = reader.read::<u32>()?;
+    ///     // only reads 2 bytes!
+    ///     let next_value = reader.read::<u16>()?;
     ///
+    ///     // the bytes are only decoded when needed
+    ///     let sum = value.inner() + next_value.inner() as u32;
+    ///     
+    ///     // do stuff...
+    ///     
     ///     Ok(())
     /// }
     /// ```
-    pub fn read_sized_vector<T: ByteReaderResource<'a> + Copy>(
-        &mut self,
-    ) -> Result<Vec<Chomp<T>>, ByteReaderError> {
-        let size = self.read::<u32>()?.inner() as usize;
-        self.read_n::<T>(size)
-    }
-
-    pub fn read_remaining<T: ByteReaderResource<'a> + Copy>(
-        &mut self,
-    ) -> Result<Vec<Chomp<T>>, ByteReaderError> {
-        self.read_n::<T>(self.len() / size_of::<T>())
+    pub fn read<T: ByteReaderResource<'a>>(&mut self) -> Result<Chomp<'a, T>, Error> {
+        self.read_as(self.endianness)
     }
 
-    pub fn rebase(&mut self, pos: usize) {
-        self.buf = &self.buf[pos..];
-        self.cursor = self.buf;
-    }
-}
+    /// Reads a type T from the buffer without consuming
+    ///
+    /// # Arguments
+    ///
+    /// * `T: FromBytes` - the type you want to read
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error>{
+    ///     // get buffer
+    ///     let buf = std::fs::read("test/binary.file")?;
+    ///         
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///     
+    ///     // doesn't consume the next 4 bytes!
+    ///     let full_value = reader.peek::<u32>()?.inner();
+    ///
+    ///     // does consume the next 4 bytes!
+    ///     let [first_half, second_half] = [reader.read_value::<u16>()? as u32, reader.read_value::<u16>()? as u32];
+    ///     
+    ///     // do stuff...
+    ///  
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn peek<T: ByteReaderResource<'a>>(&mut self) -> Result<Chomp<'a, T>, Error> {
+        self.peek_as(self.endianness)
+    }
+
+    /// Reads a type T from the buffer, returning the value rather than a Chomp
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"\x2A\x00\x00\x00name\0";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_value::<u32>()?, 42);
+    ///     assert_eq!(reader.read_value::<String>()?, "name");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_value<T: ByteReaderResource<'a>>(&mut self) -> Result<T, Error> {
+        let (value, size) = self.decode_next::<T>()?;
+        self.try_consume(size)?;
+        Ok(value)
+    }
+
+    /// Reads a type T from the buffer without consuming, returning the value rather than a
+    /// Chomp
+    pub fn peek_value<T: ByteReaderResource<'a>>(&self) -> Result<T, Error> {
+        Ok(self.decode_next::<T>()?.0)
+    }
+
+    /// Reads a type T from the buffer n times, returning the values rather than Chomps
+    ///
+    /// Errors without consuming if any of the values can't be read.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x00, 0x02, 0x00, 0x03, 0x00];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_n_values::<u16>(3)?, [1, 2, 3]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_n_values<T: ByteReaderResource<'a>>(&mut self, n: usize) -> Result<Vec<T>, Error> {
+        self.check_fits::<T>(n)?;
+        // a variable size n isn't checked up front, so don't trust it for the capacity
+        let mut values = Vec::with_capacity(n.min(self.len()));
+        let mut rest = self.cursor;
+        for _ in 0..n {
+            let (value, size) = self.decode_value::<T>(rest, self.endianness)?;
+            values.push(value);
+            rest = &rest[size..];
+        }
+        self.try_consume(self.len() - rest.len())?;
+        Ok(values)
+    }
+
+    /// Reads as many whole values of a fixed size T as remain, up to n
+    ///
+    /// Running out of bytes isn't an error, so this consumes exactly `T::SIZE` bytes for each
+    /// value returned and leaves any trailing partial value unread. Errors without consuming
+    /// if any of the values can't be converted, and with `VariableSize` if T isn't a fixed
+    /// size.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     // the last record is still being written
+    ///     let buf = [0x01, 0x00, 0x02, 0x00, 0x03];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_up_to::<u16>(8)?, [1, 2]);
+    ///     assert_eq!(reader.remaining(), [0x03]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_up_to<T: ByteReaderResource<'a>>(&mut self, n: usize) -> Result<Vec<T>, Error> {
+        let count = self.remaining_count::<T>()?;
+        self.read_n_values(n.min(count))
+    }
+
+    /// Returns how many whole values of a fixed size T remain in the buffer
+    ///
+    /// Errors with `VariableSize` if T isn't a fixed size, as its values can't be counted
+    /// without decoding them.
+    pub fn remaining_count<T: ByteReaderResource<'a>>(&self) -> Result<usize, Error> {
+        if T::VARIABLE_SIZE {
+            return Err(self.err(ErrorKind::VariableSize));
+        }
+        Ok(self.len().checked_div(T::SIZE).unwrap_or(usize::MAX))
+    }
+
+    /// Reads a type T from the buffer n times as a ChompSlice, which decodes the values as
+    /// they're accessed rather than allocating a Chomp for each
+    ///
+    /// Each value is still checked up front, so this errors without consuming if any can't
+    /// be read, and with `VariableSize` if T isn't a fixed size.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x00, 0x02, 0x00, 0x03, 0x00];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let table = reader.read_n_view::<u16>(3)?;
+    ///     assert_eq!(table.get(1), Some(2));
+    ///     assert_eq!(table.iter().sum::<u16>(), 6);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_n_view<T: ByteReaderResource<'a>>(
+        &mut self,
+        n: usize,
+    ) -> Result<ChompSlice<'a, T>, Error> {
+        if T::VARIABLE_SIZE {
+            return Err(self.err(ErrorKind::VariableSize));
+        }
+        self.check_fits::<T>(n)?;
+        let bytes = &self.cursor[..n * T::SIZE];
+        // decode each value up front so invalid bytes error here rather than on access
+        for value in bytes.chunks_exact(T::SIZE.max(1)).take(n) {
+            self.decode_value::<T>(value, self.endianness)?;
+        }
+        self.try_consume(bytes.len())?;
+        Ok(ChompSlice {
+            bytes,
+            len: n,
+            endianness: self.endianness,
+            resource_type: PhantomData,
+        })
+    }
+
+    /// Decodes the T at the cursor, returning it and its size
+    fn decode_next<T: ByteReaderResource<'a>>(&self) -> Result<(T, usize), Error> {
+        self.check_fits::<T>(1)?;
+        self.decode_value::<T>(self.cursor, self.endianness)
+    }
+
+    /// Errors with `UnexpectedEof` if n values of a fixed size T don't fit in the rest of
+    /// the buffer
+    fn check_fits<T: ByteReaderResource<'a>>(&self, n: usize) -> Result<(), Error> {
+        if T::VARIABLE_SIZE {
+            return Ok(());
+        }
+        match n.checked_mul(T::SIZE) {
+            Some(len) if len <= self.len() => Ok(()),
+            len => Err(self.eof_err::<T>(len.unwrap_or(usize::MAX), self.len())),
+        }
+    }
+
+    /// Returns a copy of the reader with its cursor at `pos`, without the saved positions
+    fn at(&self, pos: usize) -> Result<ByteReader<'a>, Error> {
+        if pos > self.buf.len() {
+            return Err(self.err(ErrorKind::SeekOutOfRange {
+                position: pos as i128,
+                len: self.buf.len(),
+            }));
+        }
+        Ok(ByteReader {
+            buf: self.buf,
+            cursor: &self.buf[pos..],
+            endianness: self.endianness,
+            origin: self.origin,
+            positions: Vec::new(),
+            error_snippets: self.error_snippets,
+        })
+    }
+
+    /// Reads a type T at a position in the buffer without moving the cursor
+    ///
+    /// Like `seek`, `pos` is measured from the start of this reader's buffer, which is
+    /// the base after a `rebase` rather than the start of the original buffer. Errors
+    /// with `SeekOutOfRange` if `pos` is past the end of the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     // an offset to a value stored at the end
+    ///     let buf = [0x03, 0x00, 0x00, 0x2A];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let offset = reader.read::<u8>()?.inner() as usize;
+    ///     assert_eq!(reader.read_at::<u8>(offset)?.inner(), 0x2A);
+    ///     assert_eq!(reader.cursor(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_at<T: ByteReaderResource<'a>>(&self, pos: usize) -> Result<Chomp<'a, T>, Error> {
+        Ok(self.read_n_at(pos, 1)?[0])
+    }
+
+    /// Reads a type T n times at a position in the buffer without moving the cursor
+    ///
+    /// See `read_at` for how `pos` is measured.
+    pub fn read_n_at<T: ByteReaderResource<'a>>(
+        &self,
+        pos: usize,
+        n: usize,
+    ) -> Result<Vec<Chomp<'a, T>>, Error> {
+        self.at(pos)?.peek_n(n)
+    }
+
+    /// Errors with `PastEnd` unless `len` bytes remain `offset` bytes after the cursor
+    fn check_ahead(&self, offset: usize, len: usize) -> Result<(), Error> {
+        let end = offset.saturating_add(len);
+        if end > self.len() {
+            return Err(self.err(ErrorKind::PastEnd {
+                overrun: end - self.len(),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Reads a type T `offset` bytes after the cursor without consuming
+    ///
+    /// Errors with `PastEnd` if the value would end past the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"\x01\x02DATA";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     if reader.peek_at::<[u8; 4]>(2)?.inner() == *b"DATA" {
+    ///         assert_eq!(reader.read::<u16>()?.inner(), 0x0201);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn peek_at<T: ByteReaderResource<'a>>(&self, offset: usize) -> Result<Chomp<'a, T>, Error> {
+        self.check_ahead(offset, if T::VARIABLE_SIZE { 0 } else { T::SIZE })?;
+        Ok(self.at(self.cursor() + offset)?.peek_n(1)?[0])
+    }
+
+    /// Returns the `len` bytes `offset` bytes after the cursor without consuming
+    ///
+    /// Errors with `PastEnd` if the bytes would end past the buffer.
+    pub fn peek_bytes_at(&self, offset: usize, len: usize) -> Result<&'a [u8], Error> {
+        self.check_ahead(offset, len)?;
+        Ok(&self.cursor[offset..offset + len])
+    }
+
+    /// Gets the size of the buffer
+    pub fn size(&self) -> usize {
+        self.buf.len()
+    }
+
+    // std::mem::size_of<T> for T: ByteReaderResource + Size
+    pub fn byte_size<T: ByteReaderResource<'a>>(&mut self) -> Result<usize, Error> {
+        let (_, s) = T::try_from_bytes(self.cursor.into(), self.endianness)
+            .map_err(|e| self.err(ErrorKind::Decode(TryFromBytesError::custom(e))))?;
+        Ok(s)
+    }
+
+    /// Reads a type T from the buffer
+    ///
+    /// # Arguments
+    ///
+    /// * `T: FromBytes` - the type you want to read
+    /// * `n: usize` - the number of T to read
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, ChompFlatten, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///
+    ///     // get buffer
+    ///     let buf = std::fs::read("test/binary.file")?;
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     // read 32 bytes (or 16 u16s)
+    ///     let values = reader.read_n::<u16>(16)?.flatten();
+    ///     
+    ///     // do stuff ...
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_n<T: ByteReaderResource<'a> + Sized>(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<Chomp<'a, T>>, Error> {
+        // handle the error here to avoid consuming bytes we don't have
+        let res = self.peek_n::<T>(n)?;
+        self.try_consume(res.iter().map(Chomp::size).sum())?;
+        Ok(res)
+    }
+    /// Reads a type T from the buffer n times without consuming
+    ///
+    /// # Arguments
+    ///
+    /// * `T: FromBytes` - the type you want to read
+    /// * `n` - the number of T to read
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, ChompFlatten, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     // get buffer
+    ///     let buf = std::fs::read("test/binary.file")?;
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     // read 32 bytes (or 16 u16s)
+    ///     let values = reader.read_n::<u16>(16)?.flatten();
+    ///
+    ///     // ... do stuff
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn peek_n<T: ByteReaderResource<'a> + Sized>(
+        &self,
+        n: usize,
+    ) -> Result<Vec<Chomp<'a, T>>, Error> {
+        self.peek_n_as(n, self.endianness)
+    }
+
+    /// Reads a type T in `endianness` rather than the reader's endianness
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     // a little endian record with a port in network order
+    ///     let buf = [0x01, 0x00, 0x1F, 0x90];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read::<u16>()?.inner(), 1);
+    ///     assert_eq!(reader.read_as::<u16>(Endianness::Big)?.inner(), 8080);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_as<T: ByteReaderResource<'a>>(
+        &mut self,
+        endianness: Endianness,
+    ) -> Result<Chomp<'a, T>, Error> {
+        let res = self.peek_as::<T>(endianness)?;
+        // advance by the size actually decoded, which for variable size types isn't SIZE
+        self.try_consume(res.size())?;
+        Ok(res)
+    }
+
+    /// Reads a big endian T, whatever the reader's endianness
+    pub fn read_be<T: ByteReaderResource<'a>>(&mut self) -> Result<Chomp<'a, T>, Error> {
+        self.read_as(Endianness::Big)
+    }
+
+    /// Reads a little endian T, whatever the reader's endianness
+    pub fn read_le<T: ByteReaderResource<'a>>(&mut self) -> Result<Chomp<'a, T>, Error> {
+        self.read_as(Endianness::Little)
+    }
+
+    /// Reads a type T in `endianness` without consuming
+    pub fn peek_as<T: ByteReaderResource<'a>>(
+        &self,
+        endianness: Endianness,
+    ) -> Result<Chomp<'a, T>, Error> {
+        self.check_fits::<T>(1)?;
+        let (_, size) = self.decode_value::<T>(self.cursor, endianness)?;
+        Ok(Chomp::with_endianness(&self.cursor[..size], endianness))
+    }
+
+    /// Reads a big endian T without consuming
+    pub fn peek_be<T: ByteReaderResource<'a>>(&self) -> Result<Chomp<'a, T>, Error> {
+        self.peek_as(Endianness::Big)
+    }
+
+    /// Reads a little endian T without consuming
+    pub fn peek_le<T: ByteReaderResource<'a>>(&self) -> Result<Chomp<'a, T>, Error> {
+        self.peek_as(Endianness::Little)
+    }
+
+    /// Reads a type T n times in `endianness`
+    pub fn read_n_as<T: ByteReaderResource<'a>>(
+        &mut self,
+        n: usize,
+        endianness: Endianness,
+    ) -> Result<Vec<Chomp<'a, T>>, Error> {
+        let res = self.peek_n_as::<T>(n, endianness)?;
+        self.try_consume(res.iter().map(Chomp::size).sum())?;
+        Ok(res)
+    }
+
+    /// Reads a big endian T n times
+    pub fn read_n_be<T: ByteReaderResource<'a>>(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<Chomp<'a, T>>, Error> {
+        self.read_n_as(n, Endianness::Big)
+    }
+
+    /// Reads a little endian T n times
+    pub fn read_n_le<T: ByteReaderResource<'a>>(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<Chomp<'a, T>>, Error> {
+        self.read_n_as(n, Endianness::Little)
+    }
+
+    /// Reads a type T n times in `endianness` without consuming
+    pub fn peek_n_as<T: ByteReaderResource<'a>>(
+        &self,
+        n: usize,
+        endianness: Endianness,
+    ) -> Result<Vec<Chomp<'a, T>>, Error> {
+        self.check_fits::<T>(n)?;
+        // decode each value up front so invalid bytes error here rather than in Chomp::inner
+        // a variable size n isn't checked up front, so don't trust it for the capacity
+        let mut chomps = Vec::with_capacity(n.min(self.len()));
+        let mut rest = self.cursor;
+        for _ in 0..n {
+            let (_, size) = self.decode_value::<T>(rest, endianness)?;
+            chomps.push(Chomp::with_endianness(&rest[..size], endianness));
+            rest = &rest[size..];
+        }
+        Ok(chomps)
+    }
+
+    /// Decodes a T from the start of `rest`, returning it and its size
+    ///
+    /// Fixed size types must have already been checked to fit in `rest`.
+    fn decode_value<T: ByteReaderResource<'a>>(
+        &self,
+        rest: &[u8],
+        endianness: Endianness,
+    ) -> Result<(T, usize), Error> {
+        let bytes = if T::VARIABLE_SIZE {
+            rest
+        } else {
+            &rest[..T::SIZE]
+        };
+        let (value, size) = T::try_from_slice(bytes, endianness)
+            .map_err(|e| self.err(ErrorKind::Decode(TryFromBytesError::custom(e))))?;
+        // a variable size value may claim bytes past the end, like an unterminated String
+        if size > rest.len() {
+            return Err(self.eof_err::<T>(size, rest.len()));
+        }
+        Ok((value, size))
+    }
+
+    /// Reads `out.len()` values of type T into `out`
+    ///
+    /// Errors without consuming if any value can't be read, though `out` may have been
+    /// partly written to.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x00, 0x02, 0x00];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let mut values = [0u16; 2];
+    ///     reader.read_n_into(&mut values)?;
+    ///     assert_eq!(values, [1, 2]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_n_into<T: ByteReaderResource<'a>>(&mut self, out: &mut [T]) -> Result<(), Error> {
+        self.check_fits::<T>(out.len())?;
+        let mut rest = self.cursor;
+        for slot in out {
+            let (value, size) = self.decode_value::<T>(rest, self.endianness)?;
+            *slot = value;
+            rest = &rest[size..];
+        }
+        self.try_consume(self.len() - rest.len())
+    }
+
+    /// Clears `out` and reads `n` values of type T into it, reusing its capacity
+    ///
+    /// Errors without consuming if any value can't be read.
+    pub fn read_n_into_vec<T: ByteReaderResource<'a>>(
+        &mut self,
+        out: &mut Vec<T>,
+        n: usize,
+    ) -> Result<(), Error> {
+        out.clear();
+        self.check_fits::<T>(n)?;
+        out.reserve(n);
+        let mut rest = self.cursor;
+        for _ in 0..n {
+            let (value, size) = self.decode_value::<T>(rest, self.endianness)?;
+            out.push(value);
+            rest = &rest[size..];
+        }
+        self.try_consume(self.len() - rest.len())
+    }
+
+    /// Splits the remaining bytes at `offset` from the cursor into two readers with their
+    /// own cursors, leaving this reader untouched
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x02, 0x03];
+    ///     let reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let (mut head, mut tail) = reader.split_at(1)?;
+    ///     assert_eq!(head.read::<u8>()?.inner(), 0x01);
+    ///     assert_eq!(tail.read::<u16>()?.inner(), 0x0302);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn split_at(&self, offset: usize) -> Result<(ByteReader<'a>, ByteReader<'a>), Error> {
+        if offset > self.len() {
+            return Err(self.err(ErrorKind::SplitOutOfRange {
+                offset,
+                remaining: self.len(),
+            }));
+        }
+        Ok((
+            self.section(0, offset),
+            self.section(offset, self.len() - offset),
+        ))
+    }
+
+    /// Skips `n` bytes, erroring with `UnexpectedEof` without moving if fewer remain
+    pub fn skip_bytes(&mut self, n: usize) -> Result<(), Error> {
+        if self.len() < n {
+            return Err(self.eof_err::<[u8]>(n, self.len()));
+        }
+        self.consume(n);
+        Ok(())
+    }
+
+    /// Advances past `amt` bytes, erroring with `UnexpectedEof` without moving if fewer
+    /// remain
+    ///
+    /// Unlike `BufRead::consume`, which saturates at the end of the buffer, this reports
+    /// over-consumption as an error.
+    pub fn try_consume(&mut self, amt: usize) -> Result<(), Error> {
+        if self.len() < amt {
+            return Err(self.eof_err::<[u8]>(amt, self.len()));
+        }
+        self.consume(amt);
+        Ok(())
+    }
+
+    /// Skips over a T, returning the number of bytes skipped
+    ///
+    /// Variable size types like String are decoded to find their size.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"\x00\x00\x00\x00name\0\x2A";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     // skip a reserved u32 and a name
+    ///     reader.skip::<u32>()?;
+    ///     assert_eq!(reader.skip::<String>()?, 5);
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 42);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn skip<T: ByteReaderResource<'a>>(&mut self) -> Result<usize, Error> {
+        self.skip_n::<T>(1)
+    }
+
+    /// Skips over `n` values of T, returning the number of bytes skipped
+    pub fn skip_n<T: ByteReaderResource<'a>>(&mut self, n: usize) -> Result<usize, Error> {
+        let size = if T::VARIABLE_SIZE {
+            self.peek_n::<T>(n)?.iter().map(Chomp::size).sum()
+        } else {
+            self.check_fits::<T>(n)?;
+            n * T::SIZE
+        };
+        self.consume(size);
+        Ok(size)
+    }
+
+    /// Skips to the next multiple of `alignment` from the start of the buffer, returning
+    /// the number of bytes skipped
+    ///
+    /// Errors with `UnexpectedEof` without moving if the padding runs past the end.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x00, 0x00, 0x00, 0x2A];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     reader.read::<u8>()?;
+    ///     assert_eq!(reader.align(4)?, 3);
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 42);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn align(&mut self, alignment: usize) -> Result<usize, Error> {
+        self.align_from(0, alignment)
+    }
+
+    /// Skips to the next multiple of `alignment` from `origin`, such as the start of a
+    /// section, returning the number of bytes skipped
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - the position alignment is measured from, at or before the cursor
+    /// * `alignment` - the alignment in bytes, which must be nonzero
+    ///
+    /// Errors with `InvalidAlignment` if `alignment` is 0 or `origin` is past the cursor.
+    pub fn align_from(&mut self, origin: usize, alignment: usize) -> Result<usize, Error> {
+        let offset = match self.cursor().checked_sub(origin) {
+            Some(offset) if alignment != 0 => offset,
+            _ => return Err(self.err(ErrorKind::InvalidAlignment { origin, alignment })),
+        };
+        let padding = offset.next_multiple_of(alignment) - offset;
+        self.skip_bytes(padding)?;
+        Ok(padding)
+    }
+
+    /// Reads N bytes into an array
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"DDS |";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(&reader.read_bytes::<4>()?, b"DDS ");
+    ///     assert_eq!(reader.cursor(), 4);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let bytes = self.peek_bytes::<N>()?;
+        self.try_consume(N)?;
+        Ok(bytes)
+    }
+
+    /// Reads N bytes into an array without consuming
+    pub fn peek_bytes<const N: usize>(&self) -> Result<[u8; N], Error> {
+        if self.len() < N {
+            return Err(self.eof_err::<[u8; N]>(N, self.len()));
+        }
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(&self.cursor[..N]);
+        Ok(bytes)
+    }
+
+    /// Returns the next `n` bytes as a slice of the buffer and advances past them
+    ///
+    /// Errors with `UnexpectedEof` without consuming if fewer than `n` bytes remain.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"\x03abc";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let len = reader.read::<u8>()?.inner() as usize;
+    ///     assert_eq!(reader.read_slice(len)?, b"abc");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_slice(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        let slice = self.peek_slice(n)?;
+        self.try_consume(n)?;
+        Ok(slice)
+    }
+
+    /// Returns the next `n` bytes as a slice of the buffer without consuming
+    pub fn peek_slice(&self, n: usize) -> Result<&'a [u8], Error> {
+        if self.len() < n {
+            return Err(self.eof_err::<[u8]>(n, self.len()));
+        }
+        Ok(&self.cursor[..n])
+    }
+
+    /// Copies exactly `out.len()` bytes into `out`
+    ///
+    /// Errors with `UnexpectedEof` without consuming if fewer bytes remain.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x02, 0x03];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let mut out = [0; 2];
+    ///     reader.read_exact_bytes(&mut out)?;
+    ///     assert_eq!(out, [0x01, 0x02]);
+    ///     assert!(reader.read_exact_bytes(&mut out).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_exact_bytes(&mut self, out: &mut [u8]) -> Result<(), Error> {
+        out.copy_from_slice(self.read_slice(out.len())?);
+        Ok(())
+    }
+
+    /// Consumes the magic bytes `expected`
+    ///
+    /// Errors with `BadMagic` if the next bytes differ or `UnexpectedEof` if there aren't
+    /// enough of them, leaving the cursor untouched so another format can be tried.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"\x7FELF\x02";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     if reader.expect_magic(b"MZ").is_err() {
+    ///         reader.expect_magic(b"\x7FELF")?;
+    ///     }
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0x02);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn expect_magic(&mut self, expected: &[u8]) -> Result<(), Error> {
+        if self.len() < expected.len() {
+            return Err(self.eof_err::<[u8]>(expected.len(), self.len()));
+        }
+        let actual = &self.cursor[..expected.len()];
+        if actual != expected {
+            return Err(self.err(ErrorKind::BadMagic {
+                expected: expected.to_vec(),
+                actual: actual.to_vec(),
+            }));
+        }
+        self.consume(expected.len());
+        Ok(())
+    }
+
+    /// Consumes a u32 magic number stored in the reader's endianness
+    ///
+    /// Errors like `expect_magic`.
+    pub fn expect_magic_u32(&mut self, value: u32) -> Result<(), Error> {
+        self.expect_magic(&value.to_bytes(self.endianness))
+    }
+
+    /// Returns the offset from the cursor of the next occurrence of `needle`, without
+    /// consuming
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness};
+    ///
+    /// let buf = *b"junk\x89PNG";
+    /// let reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    /// assert_eq!(reader.find(b"\x89PNG"), Some(4));
+    /// assert_eq!(reader.find(b"GIF"), None);
+    /// ```
+    pub fn find(&self, needle: &[u8]) -> Option<usize> {
+        memchr::memmem::find(self.cursor, needle)
+    }
+
+    /// Advances to the next occurrence of `needle`, returning how many bytes were skipped
+    ///
+    /// Errors with `NoPattern` if `needle` isn't found, leaving the cursor untouched.
+    pub fn seek_to_pattern(&mut self, needle: &[u8]) -> Result<usize, Error> {
+        let offset = self
+            .find(needle)
+            .ok_or_else(|| self.err(ErrorKind::NoPattern))?;
+        self.consume(offset);
+        Ok(offset)
+    }
+
+    /// Reads the bytes up to `delimiter`, consuming the delimiter but not returning it
+    ///
+    /// Errors with `NoDelimiter` if the delimiter isn't found, leaving the cursor
+    /// untouched. Use `read_until_or_eof` to take the rest of the buffer instead.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x02, 0xFF, 0x03];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_until(0xFF)?, [0x01, 0x02]);
+    ///     assert!(reader.read_until(0xFF).is_err());
+    ///     assert_eq!(reader.read_until_or_eof(0xFF), [0x03]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_until(&mut self, delimiter: u8) -> Result<&'a [u8], Error> {
+        let cursor = self.cursor;
+        let pos = cursor
+            .iter()
+            .position(|&byte| byte == delimiter)
+            .ok_or_else(|| self.err(ErrorKind::NoDelimiter { delimiter }))?;
+        self.consume(pos + 1);
+        Ok(&cursor[..pos])
+    }
+
+    /// Reads the bytes up to `delimiter` or the end of the buffer, consuming the
+    /// delimiter if there is one
+    pub fn read_until_or_eof(&mut self, delimiter: u8) -> &'a [u8] {
+        let cursor = self.cursor;
+        match cursor.iter().position(|&byte| byte == delimiter) {
+            Some(pos) => {
+                self.consume(pos + 1);
+                &cursor[..pos]
+            }
+            None => {
+                self.consume(cursor.len());
+                cursor
+            }
+        }
+    }
+
+    /// Reads a line of UTF-8 text ending in `\n`, stripping the newline and any `\r` before it
+    ///
+    /// Errors with `NoDelimiter` if there's no newline or with the UTF-8 error if the line
+    /// isn't valid text, leaving the cursor at the start of the line in both cases.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"P5\r\n\x2A";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_line()?, "P5");
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0x2A);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_line(&mut self) -> Result<String, Error> {
+        let mut reader = self.clone();
+        let line = reader.read_until(b'\n')?;
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let value = self.decode_utf8(line, 0)?;
+        *self = reader;
+        Ok(value)
+    }
+
+    /// Reads a bool from the buffer, erroring on any byte other than 0x00 or 0x01
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0xCC];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert!(reader.read_strict_bool()?);
+    ///     // 0xCC is not a valid bool, the cursor is not moved
+    ///     assert!(reader.read_strict_bool().is_err());
+    ///     // ... but a lenient read accepts it
+    ///     assert!(reader.read::<bool>()?.inner());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_strict_bool(&mut self) -> Result<bool, Error> {
+        let byte = self.peek::<u8>()?.inner();
+        if byte > 0x01 {
+            return Err(self.err(ErrorKind::Decode(TryFromBytesError::InvalidBool(byte))));
+        }
+        self.consume(1);
+        Ok(byte == 0x01)
+    }
+
+    /// Reads a presence byte followed by a T if the byte is nonzero
+    ///
+    /// If the presence byte is set but the T can't be read, nothing is consumed.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x2A, 0x00, 0x00, 0x00, 0x00];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_optional::<u32>()?, Some(42));
+    ///     assert_eq!(reader.read_optional::<u32>()?, None);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_optional<T: ByteReaderResource<'a>>(&mut self) -> Result<Option<T>, Error> {
+        if self.peek::<u8>()?.inner() == 0 {
+            self.consume(1);
+            return Ok(None);
+        }
+        let mut reader = self.clone();
+        reader.consume(1);
+        let value = reader.read::<T>()?.inner();
+        *self = reader;
+        Ok(Some(value))
+    }
+
+    /// Reads a NUL-terminated string
+    ///
+    /// A missing terminator is an `UnexpectedEof` error and the cursor is left untouched.
+    /// Use `read_cstr` to get an `UnterminatedString` error instead, or
+    /// `read_string_lenient` to treat the end of the buffer as the terminator. Invalid
+    /// UTF-8 is an `InvalidUtf8` error locating the first bad byte.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error, ErrorKind};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"ok\0caf\xC3\0";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_string()?, "ok");
+    ///     let err = reader.read_string().unwrap_err();
+    ///     assert!(matches!(err.kind(), ErrorKind::InvalidUtf8 { offset: 6, .. }));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_string(&mut self) -> Result<String, Error> {
+        let len = self.string_len()?;
+        let value = self.decode_utf8(&self.cursor[..len], 0)?;
+        self.consume(len + 1);
+        Ok(value)
+    }
+
+    /// Reads a NUL-terminated string, replacing invalid UTF-8 with U+FFFD
+    ///
+    /// This never fails on the encoding, but a missing terminator is still an
+    /// `UnexpectedEof` error, like `read_string`.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"caf\xC3\0";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_string_lossy()?, "caf\u{FFFD}");
+    ///     assert!(reader.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_string_lossy(&mut self) -> Result<String, Error> {
+        let len = self.string_len()?;
+        let value = String::from_utf8_lossy(&self.cursor[..len]).into_owned();
+        self.consume(len + 1);
+        Ok(value)
+    }
+
+    /// Returns the length of the NUL-terminated string at the cursor, excluding the
+    /// terminator
+    fn string_len(&self) -> Result<usize, Error> {
+        self.cursor.iter().position(|&b| b == 0x00).ok_or_else(|| {
+            // String claims the terminator as well as the bytes that are there
+            self.eof_err::<String>(self.len() + 1, self.len())
+        })
+    }
+
+    /// Converts `bytes`, which start `offset` bytes past the cursor, into a String
+    ///
+    /// Errors with `InvalidUtf8` at the absolute position of the first invalid byte.
+    pub(crate) fn decode_utf8(&self, bytes: &[u8], offset: usize) -> Result<String, Error> {
+        String::from_utf8(bytes.to_vec()).map_err(|error| {
+            let offset = self.absolute_cursor() + offset + error.utf8_error().valid_up_to();
+            self.err(ErrorKind::InvalidUtf8 { offset, error })
+        })
+    }
+
+    /// Reads a string up to a NUL or the end of the buffer, whichever comes first
+    ///
+    /// The terminator is consumed if there is one. This suits formats that leave off the
+    /// final terminator, but on a truncated buffer it silently returns part of the string,
+    /// so prefer `read_string` or `read_cstr` otherwise. Errors with `UnexpectedEof` if the
+    /// buffer is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"first\0last";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_string_lenient()?, "first");
+    ///     assert_eq!(reader.read_string_lenient()?, "last");
+    ///     assert!(reader.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_string_lenient(&mut self) -> Result<String, Error> {
+        if self.is_empty() {
+            return Err(self.eof_err::<String>(1, 0));
+        }
+        let (len, terminated) = match self.cursor.iter().position(|&b| b == 0x00) {
+            Some(len) => (len, true),
+            None => (self.len(), false),
+        };
+        let value = self.decode_utf8(&self.cursor[..len], 0)?;
+        self.consume(len + terminated as usize);
+        Ok(value)
+    }
+
+    /// Reads a NUL-terminated string, erroring if the buffer ends before the terminator
+    ///
+    /// Unlike `read_string`, a missing terminator is an `UnterminatedString` error and
+    /// the cursor is left untouched.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"chomp\0trunc";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_cstr()?, "chomp");
+    ///     assert!(reader.read_cstr().is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_cstr(&mut self) -> Result<String, Error> {
+        let len = self.cursor.iter().position(|&b| b == 0x00).ok_or_else(|| {
+            self.err(ErrorKind::UnterminatedString {
+                scanned: self.len(),
+            })
+        })?;
+        let value = self.decode_utf8(&self.cursor[..len], 0)?;
+        self.consume(len + 1);
+        Ok(value)
+    }
+
+    /// Reads a string stored in a fixed width field, always consuming `width` bytes
+    ///
+    /// The string ends at the first NUL in the field, or at the end of the field if there is none.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - the size of the field in bytes
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"name\0\0\0\0\x2A";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_string_fixed(8)?, "name");
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 42);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_string_fixed(&mut self, width: usize) -> Result<String, Error> {
+        if self.len() < width {
+            return Err(self.eof_err::<String>(width, self.len()));
+        }
+        let field = &self.cursor[..width];
+        let len = field.iter().position(|&b| b == 0x00).unwrap_or(width);
+        let value = self.decode_utf8(&field[..len], 0)?;
+        self.consume(width);
+        Ok(value)
+    }
+
+    /// Reads a string stored in a fixed width field like `read_string_fixed`, trimming
+    /// trailing space padding
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - the size of the field in bytes
+    pub fn read_string_fixed_trimmed(&mut self, width: usize) -> Result<String, Error> {
+        let value = self.read_string_fixed(width)?;
+        Ok(value.trim_end_matches(' ').to_owned())
+    }
+
+    /// Reads a NUL padded string from a fixed width field, always consuming `width` bytes
+    ///
+    /// The string ends at the first NUL, or fills the whole field if there is none. When
+    /// `strict` is set every byte after the first NUL must also be NUL, otherwise the read
+    /// fails with `TryFromBytesError::NonZeroPadding` naming the first offending byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - the size of the field in bytes
+    /// * `strict` - whether to check that the padding is all NUL
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"file\0\0\0\0readme\0x";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_padded_string(8, true)?, "file");
+    ///     assert!(reader.read_padded_string(8, true).is_err());
+    ///     assert_eq!(reader.read_padded_string(8, false)?, "readme");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_padded_string(&mut self, width: usize, strict: bool) -> Result<String, Error> {
+        if strict && self.len() >= width {
+            let field = &self.cursor[..width];
+            let len = field.iter().position(|&b| b == 0x00).unwrap_or(width);
+            if let Some(pos) = field[len..].iter().position(|&b| b != 0x00) {
+                let padding = TryFromBytesError::NonZeroPadding {
+                    offset: self.absolute_cursor() + len + pos,
+                    byte: field[len + pos],
+                };
+                return Err(self.err(padding.into()));
+            }
+        }
+        self.read_string_fixed(width)
+    }
+
+    /// Reads a string prefixed by a single length byte, with no terminator
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"\x05chomp";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_pascal_string()?, "chomp");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_pascal_string(&mut self) -> Result<String, Error> {
+        let len = self.peek::<u8>()?.inner() as usize;
+        if self.len() - 1 < len {
+            return Err(self.eof_err::<String>(1 + len, self.len()));
+        }
+        let value = self.decode_utf8(&self.cursor[1..=len], 1)?;
+        self.consume(1 + len);
+        Ok(value)
+    }
+
+    /// Reads a UTF-16 string terminated by a 0x0000 code unit, consuming the terminator
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [b'h', 0, b'i', 0, 0, 0, 0xFF];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_utf16_string()?, "hi");
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0xFF);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_utf16_string(&mut self) -> Result<String, Error> {
+        let len = self
+            .utf16_units()
+            .position(|unit| unit == 0x0000)
+            // the string claims a terminator after the whole units that are there
+            .ok_or_else(|| self.eof_err::<String>(self.len() / 2 * 2 + 2, self.len()))?;
+        let value = self.decode_utf16(len)?;
+        self.consume((len + 1) * 2);
+        Ok(value)
+    }
+
+    /// Reads a UTF-16 string of exactly `len` code units, with no terminator
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - the number of u16 code units in the string
+    pub fn read_utf16_string_len(&mut self, len: usize) -> Result<String, Error> {
+        if self.len() / 2 < len {
+            return Err(self.eof_err::<String>(len.saturating_mul(2), self.len()));
+        }
+        let value = self.decode_utf16(len)?;
+        self.consume(len * 2);
+        Ok(value)
+    }
+
+    fn utf16_units(&self) -> impl Iterator<Item = u16> + 'a {
+        let endianness = self.endianness;
+        self.cursor
+            .chunks_exact(2)
+            .map(move |unit| match endianness {
+                Endianness::Big => u16::from_be_bytes([unit[0], unit[1]]),
+                Endianness::Little => u16::from_le_bytes([unit[0], unit[1]]),
+            })
+    }
+
+    fn decode_utf16(&self, len: usize) -> Result<String, Error> {
+        String::from_utf16(&self.utf16_units().take(len).collect::<Vec<u16>>())
+            .map_err(|e| self.err(ErrorKind::Decode(e.into())))
+    }
+
+    /// Reads a type T from the buffer n times without consuming
+    ///
+    /// # Arguments
+    ///
+    /// * `T: FromBytes` - the type you want to read
+    /// * `n` - the number of T to read
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, ChompFlatten, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     // get buffer
+    ///     let buf = std::fs::read("test/binary.file")?;
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     // read 32 bytes (or 16 u16s)
+    ///     let values = reader.read_n::<u16>(16)?.flatten();
+    ///
+    ///     // ... do stuff
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_sized_vector<T: ByteReaderResource<'a>>(
+        &mut self,
+    ) -> Result<Vec<Chomp<'a, T>>, Error> {
+        self.read_sized_vector_with::<u32, T>()
+    }
+
+    /// Reads a vector of T prefixed by its length as an L
+    ///
+    /// Errors with `DeclaredSizeTooLarge` before reading any values if a fixed size T
+    /// can't fit the declared length in the rest of the buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `L` - the integer type of the length prefix
+    /// * `T: FromBytes` - the type you want to read
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, ChompFlatten, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x00, 0x02, 0x00, 0x0A, 0x00, 0x0B];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Big);
+    ///
+    ///     let values = reader.read_sized_vector_with::<u16, u16>()?.flatten();
+    ///     assert_eq!(values, vec![10, 11]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_sized_vector_with<L, T>(&mut self) -> Result<Vec<Chomp<'a, T>>, Error>
+    where
+        L: ByteReaderResource<'a> + TryInto<usize>,
+        T: ByteReaderResource<'a>,
+    {
+        let mut reader = self.clone();
+        let size = reader
+            .read::<L>()?
+            .inner()
+            .try_into()
+            .or(Err(self.err(ErrorKind::LengthOverflow)))?;
+        // check the declared size fits before reading, as it may be corrupt or hostile
+        if !T::VARIABLE_SIZE
+            && size
+                .checked_mul(T::SIZE)
+                .is_none_or(|len| len > reader.len())
+        {
+            return Err(self.err(ErrorKind::DeclaredSizeTooLarge {
+                declared: size,
+                remaining: reader.len(),
+            }));
+        }
+        let values = reader.read_n::<T>(size)?;
+        *self = reader;
+        Ok(values)
+    }
+
+    /// Reads values of T until `sentinel`, consuming but not returning the sentinel
+    ///
+    /// Errors with `NoSentinel` if the buffer ends first, leaving the cursor untouched.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x00, 0x02, 0x00, 0xFF, 0xFF];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_terminated::<u16>(0xFFFF)?, vec![1, 2]);
+    ///     assert!(reader.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_terminated<T>(&mut self, sentinel: T) -> Result<Vec<T>, Error>
+    where
+        T: ByteReaderResource<'a> + PartialEq,
+    {
+        self.read_terminated_with(sentinel, true)
+    }
+
+    /// Reads values of T until `sentinel`, leaving the cursor on the sentinel if
+    /// `consume_sentinel` is false
+    pub fn read_terminated_with<T>(
+        &mut self,
+        sentinel: T,
+        consume_sentinel: bool,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: ByteReaderResource<'a> + PartialEq,
+    {
+        let mut reader = self.clone();
+        let mut values = Vec::new();
+        loop {
+            let before = reader.clone();
+            let value = match reader.read::<T>() {
+                Ok(value) => value.inner(),
+                Err(e) if e.kind.is_eof() => {
+                    return Err(self.err(ErrorKind::NoSentinel { read: values.len() }))
+                }
+                Err(e) => return Err(e),
+            };
+            if value == sentinel {
+                if !consume_sentinel {
+                    reader = before;
+                }
+                break;
+            }
+            values.push(value);
+        }
+        *self = reader;
+        Ok(values)
+    }
+
+    /// Reads values of T until `sentinel` without consuming
+    pub fn peek_terminated<T>(&self, sentinel: T) -> Result<Vec<T>, Error>
+    where
+        T: ByteReaderResource<'a> + PartialEq,
+    {
+        self.clone().read_terminated(sentinel)
+    }
+
+    /// Reads a block prefixed by its length in bytes as an L, parsing it with `f`
+    ///
+    /// `f` is given a ByteReader limited to the block, whose cursor starts at 0. The cursor
+    /// ends up after the block however much of it `f` read, and is left untouched if the
+    /// block doesn't fit in the buffer or `f` errors.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x03, 0x0A, 0x0B, 0x0C, 0xFF];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let first = reader.read_prefixed::<u8, _>(|block| Ok(block.read::<u8>()?.inner()))?;
+    ///     assert_eq!(first, 0x0A);
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0xFF);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_prefixed<L, R>(
+        &mut self,
+        f: impl FnOnce(&mut ByteReader<'a>) -> Result<R, Error>,
+    ) -> Result<R, Error>
+    where
+        L: ByteReaderResource<'a> + TryInto<usize>,
+    {
+        self.read_prefixed_with::<L, R>(false, f)
+    }
+
+    /// Reads a length prefixed block like `read_prefixed`, erroring with `UnconsumedBytes`
+    /// if `f` doesn't read the whole block
+    pub fn read_prefixed_strict<L, R>(
+        &mut self,
+        f: impl FnOnce(&mut ByteReader<'a>) -> Result<R, Error>,
+    ) -> Result<R, Error>
+    where
+        L: ByteReaderResource<'a> + TryInto<usize>,
+    {
+        self.read_prefixed_with::<L, R>(true, f)
+    }
+
+    fn read_prefixed_with<L, R>(
+        &mut self,
+        strict: bool,
+        f: impl FnOnce(&mut ByteReader<'a>) -> Result<R, Error>,
+    ) -> Result<R, Error>
+    where
+        L: ByteReaderResource<'a> + TryInto<usize>,
+    {
+        let prefix = self.peek::<L>()?;
+        let len: usize = prefix
+            .inner()
+            .try_into()
+            .or(Err(self.err(ErrorKind::LengthOverflow)))?;
+        // variable size prefixes take however many bytes they decoded from, not L::SIZE
+        let start = prefix.size();
+        if self.len() - start < len {
+            return Err(self.eof_err::<[u8]>(start + len, self.len()));
+        }
+        let mut block = self.section(start, len);
+        let value = f(&mut block)?;
+        if strict && !block.is_empty() {
+            return Err(self.err(ErrorKind::UnconsumedBytes {
+                remaining: block.len(),
+            }));
+        }
+        self.consume(start + len);
+        Ok(value)
+    }
+
+    /// Reads as many T as fit in the rest of the buffer
+    pub fn read_remaining<T: ByteReaderResource<'a>>(
+        &mut self,
+    ) -> Result<Vec<Chomp<'a, T>>, Error> {
+        if !T::VARIABLE_SIZE {
+            return self.read_n::<T>(self.len() / T::SIZE);
+        }
+        let mut reader = self.clone();
+        let mut chomps = Vec::new();
+        while !reader.is_empty() {
+            chomps.push(reader.read::<T>()?);
+        }
+        *self = reader;
+        Ok(chomps)
+    }
+
+    /// Makes `pos` the start of the buffer, so the cursor and `seek` are measured from it
+    ///
+    /// Errors with `SeekOutOfRange` if `pos` is past the end of the buffer. Errors and
+    /// `absolute_cursor` keep reporting positions in the original buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x00, 0x00, 0x01, 0x02];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     reader.rebase(2)?;
+    ///     assert_eq!(reader.read::<u8>()?.inner(), 0x01);
+    ///     assert_eq!((reader.cursor(), reader.absolute_cursor()), (1, 3));
+    ///     assert!(reader.rebase(3).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn rebase(&mut self, pos: usize) -> Result<(), Error> {
+        if pos > self.buf.len() {
+            return Err(self.err(ErrorKind::SeekOutOfRange {
+                position: pos as i128,
+                len: self.buf.len(),
+            }));
+        }
+        self.origin += pos;
+        self.buf = &self.buf[pos..];
+        self.cursor = self.buf;
+        Ok(())
+    }
 
+    /// Makes the cursor the start of the buffer
+    pub fn rebase_to_cursor(&mut self) {
+        self.origin += self.cursor();
+        self.buf = self.cursor;
+    }
+}
+
+/// Reads from the unread part of the buffer, advancing the cursor
 impl<'a> io::Read for ByteReader<'a> {
-    fn read(&mut self, mut buf: &mut [u8]) -> io::Result<usize> {
-        buf.write(self.buf)?;
-        // probs incorrect
-        Ok(cmp::min(self.cursor.len(), buf.len()))
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let amt = cmp::min(self.cursor.len(), buf.len());
+        buf[..amt].copy_from_slice(&self.cursor[..amt]);
+        self.consume(amt);
+        Ok(amt)
     }
 }
 
+/// `consume` saturates at the end of the buffer rather than panicking, see
+/// `ByteReader::try_consume` for a version which errors instead
 impl<'a> BufRead for ByteReader<'a> {
     fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
         Ok(self.cursor)
     }
 
     fn consume(&mut self, amt: usize) {
-        self.cursor = &self.cursor[amt..];
+        self.cursor = &self.cursor[cmp::min(amt, self.cursor.len())..];
+    }
+}
+
+/// Seeks over the whole buffer, so positions agree with `ByteReader::cursor`
+///
+/// Seeking outside of the buffer is an `InvalidInput` error.
+impl<'a> io::Seek for ByteReader<'a> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        Ok(self.seek_from(pos)? as u64)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.cursor() as u64)
     }
 }
 
-pub struct ByteReaderIterator<'a, T: TryFromBytes> {
-    buf: &'a mut ByteReader<'a>,
+/// Dumps the bytes of `buf` from `before` bytes before `pos` to `after` bytes after it,
+/// in rows of 16 labelled with their offset in the original buffer and with `pos` marked
+pub(crate) fn hex_dump(
+    buf: &[u8],
+    origin: usize,
+    pos: usize,
+    before: usize,
+    after: usize,
+) -> String {
+    const WIDTH: usize = 16;
+    let start = pos.saturating_sub(before);
+    let end = cmp::min(pos.saturating_add(after), buf.len());
+    // always include the row holding the cursor, even at the end of the buffer
+    let first = (origin + start) / WIDTH * WIDTH;
+    let last = origin + cmp::max(end, pos + 1);
+
+    let mut dump = String::new();
+    for row in (first..last).step_by(WIDTH) {
+        let mut hex = String::new();
+        let mut ascii = String::new();
+        for offset in row..row + WIDTH {
+            match offset
+                .checked_sub(origin)
+                .filter(|p| (start..end).contains(p))
+            {
+                Some(p) => {
+                    let byte = buf[p];
+                    hex += &format!("{byte:02x} ");
+                    ascii.push(if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    });
+                }
+                None => {
+                    hex += "   ";
+                    ascii.push(' ');
+                }
+            }
+        }
+        dump += &format!("{row:08x}  {hex} |{ascii}|\n");
+        if (row..row + WIDTH).contains(&(origin + pos)) {
+            let column = origin + pos - row;
+            dump += &format!("{}^^\n", " ".repeat(10 + 3 * column));
+        }
+    }
+    dump
+}
+
+pub struct ByteReaderIterator<'r, 'a, T> {
+    buf: &'r mut ByteReader<'a>,
     resource_type: PhantomData<T>,
 }
 
-impl<'a, T: ByteReaderResource<'a> + fmt::Debug> Iterator for ByteReaderIterator<'a, T> {
+impl<'r, 'a, T: ByteReaderResource<'a>> Iterator for ByteReaderIterator<'r, 'a, T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.buf.read::<T>().map(|v| v.inner()).ok()
     }
 }
+
+pub struct ByteReaderResultIterator<'r, 'a, T> {
+    buf: &'r mut ByteReader<'a>,
+    done: bool,
+    resource_type: PhantomData<T>,
+}
+
+impl<'r, 'a, T: ByteReaderResource<'a>> Iterator for ByteReaderResultIterator<'r, 'a, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.buf.is_empty() {
+            return None;
+        }
+        let value = self.buf.read::<T>().map(|v| v.inner());
+        self.done = value.is_err();
+        Some(value)
+    }
+}
+
+pub struct ByteReaderChunks<'r, 'a, T> {
+    buf: &'r mut ByteReader<'a>,
+    chunk_len: usize,
+    done: bool,
+    resource_type: PhantomData<T>,
+}
+
+impl<'r, 'a, T: ByteReaderResource<'a>> Iterator for ByteReaderChunks<'r, 'a, T> {
+    type Item = Result<Vec<T>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.buf.is_empty() {
+            return None;
+        }
+        let chunk = self
+            .buf
+            .read_n::<T>(self.chunk_len)
+            .map(|chomps| chomps.iter().map(Chomp::inner).collect());
+        self.done = chunk.is_err();
+        Some(chunk)
+    }
+}
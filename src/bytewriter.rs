@@ -1,12 +1,92 @@
-use std::mem::size_of;
-
-use super::{Endianness, ToBytes};
+use super::{Endianness, Error, ErrorKind, ToBytes};
 
+/// What went wrong writing, held by `ErrorKind::Write`
 #[derive(Debug)]
-pub enum ByteWriterError {
+#[non_exhaustive]
+pub enum WriteErrorKind {
     Fail,
+    /// The string is `len` bytes long but at most `max` bytes fit in its length prefix
+    StringTooLong {
+        len: usize,
+        max: usize,
+    },
+    /// A length of `len` doesn't fit in the length prefix type
+    LengthOverflow {
+        len: usize,
+    },
+    /// `ch` can't be represented in `encoding`
+    UnmappableChar {
+        ch: char,
+        encoding: &'static str,
+    },
+    /// Strings in `encoding` can't be NUL-terminated, as it isn't ASCII compatible
+    UnsupportedEncoding {
+        encoding: &'static str,
+    },
+    /// `time` is before the unix epoch or too far after it for the timestamp type
+    TimestampOutOfRange {
+        time: std::time::SystemTime,
+    },
+    /// `value` has too many digits for `len` bytes of packed BCD
+    BcdOverflow {
+        value: u64,
+        len: usize,
+    },
+    /// `value` is too large for the variable length encoding
+    VarintOverflow {
+        value: u64,
+    },
+    /// A write of `len` bytes at `position` doesn't fit in the `buf_len` bytes written so far
+    WriteOutOfRange {
+        position: usize,
+        len: usize,
+        buf_len: usize,
+    },
+}
+
+impl std::fmt::Display for WriteErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use WriteErrorKind::*;
+        match self {
+            Fail => write!(f, "failed to write"),
+            StringTooLong { len, max } => {
+                write!(
+                    f,
+                    "string of {len} bytes is longer than the maximum of {max}"
+                )
+            }
+            LengthOverflow { len } => write!(f, "length {len} doesn't fit in the length prefix"),
+            UnmappableChar { ch, encoding } => write!(f, "{ch:?} can't be written in {encoding}"),
+            UnsupportedEncoding { encoding } => {
+                write!(f, "{encoding} strings can't be NUL-terminated")
+            }
+            TimestampOutOfRange { time } => {
+                write!(f, "{time:?} is out of range of the timestamp")
+            }
+            BcdOverflow { value, len } => {
+                write!(f, "{value} has too many digits for {len} bytes of BCD")
+            }
+            VarintOverflow { value } => {
+                write!(f, "{value} is too large for the variable length encoding")
+            }
+            WriteOutOfRange {
+                position,
+                len,
+                buf_len,
+            } => write!(
+                f,
+                "write of {len} bytes at {position:#x} is past the end of the buffer of length \
+                 {buf_len:#x}"
+            ),
+        }
+    }
 }
 
+impl std::error::Error for WriteErrorKind {}
+
+#[deprecated(note = "use WriteErrorKind, held by ErrorKind::Write")]
+pub type ByteWriterError = WriteErrorKind;
+
 // T::Bytes: Into<Vec<u8>>
 pub trait ByteWriterResource = ToBytes<Bytes = Vec<u8>>;
 
@@ -23,49 +103,208 @@ impl ByteWriter {
         }
     }
 
+    /// Returns an Error at the end of the buffer, where the failed write would have gone
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - the kind of error to receive
+    pub fn err(&self, kind: WriteErrorKind) -> Error {
+        Error::new(ErrorKind::Write(kind), Some(self.buf.len()))
+    }
+
     pub fn append<T: ByteWriterResource>(&mut self, data: T) -> usize {
+        self.append_as(data, self.endianness)
+    }
+
+    /// Appends a borrowed value, returning the bytes written
+    ///
+    /// Borrowed strings and byte slices can be passed to `append` directly; this is for
+    /// other values that would otherwise have to be cloned.
+    ///
+    /// # Examples
+    /// ```
+    /// use bitchomp::{ByteWriter, Endianness};
+    ///
+    /// let name = String::from("chomp");
+    /// let mut writer = ByteWriter::new(Endianness::Little);
+    ///
+    /// writer.append_ref(&name);
+    /// writer.append("more");
+    /// writer.append(&[0xCA, 0xFE][..]);
+    /// assert_eq!(writer.as_bytes(), b"chomp\0more\0\xCA\xFE");
+    /// ```
+    pub fn append_ref<T: ByteWriterResource>(&mut self, data: &T) -> usize {
         let mut buf = data.to_bytes(self.endianness);
+        let size = buf.len();
         self.buf.append(&mut buf);
-        buf.len()
+        size
     }
 
-    pub fn write<T: ByteWriterResource>(
-        &mut self,
-        data: T,
-        pos: usize,
-    ) -> Result<usize, ByteWriterError> {
+    /// Appends a value in `endianness` rather than the writer's endianness, returning the
+    /// bytes written
+    pub fn append_as<T: ByteWriterResource>(&mut self, data: T, endianness: Endianness) -> usize {
+        let mut buf = data.to_bytes(endianness);
+        let size = buf.len();
+        self.buf.append(&mut buf);
+        size
+    }
+
+    /// Appends a big endian value, whatever the writer's endianness
+    pub fn append_be<T: ByteWriterResource>(&mut self, data: T) -> usize {
+        self.append_as(data, Endianness::Big)
+    }
+
+    /// Appends a little endian value, whatever the writer's endianness
+    pub fn append_le<T: ByteWriterResource>(&mut self, data: T) -> usize {
+        self.append_as(data, Endianness::Little)
+    }
+
+    /// Overwrites the bytes at `pos` with a value, returning the bytes written
+    ///
+    /// The value must fit within the bytes written so far, though it may end exactly at the
+    /// end of the buffer. Otherwise this errors with `WriteOutOfRange`, leaving the buffer
+    /// untouched.
+    pub fn write<T: ByteWriterResource>(&mut self, data: T, pos: usize) -> Result<usize, Error> {
         let buf = data.to_bytes(self.endianness);
         let size = buf.len();
-        for i in 0..size {
-            self.buf[pos + i] = buf[i];
+        match pos.checked_add(size) {
+            Some(end) if end <= self.buf.len() => {
+                self.buf[pos..end].copy_from_slice(&buf);
+                Ok(size)
+            }
+            _ => Err(Error::new(
+                ErrorKind::Write(WriteErrorKind::WriteOutOfRange {
+                    position: pos,
+                    len: size,
+                    buf_len: self.buf.len(),
+                }),
+                Some(pos),
+            )),
         }
-        Ok(buf.len())
     }
 
     pub fn len(&self) -> usize {
         self.buf.len()
     }
 
-    pub fn append_vec(&mut self, mut data: Vec<u8>) -> () {
+    /// Returns the endianness values are written in
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Sets the endianness values are written in from the next write on
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn append_vec(&mut self, mut data: Vec<u8>) {
         self.buf.append(&mut data);
     }
 
     pub fn write_vec<T: ByteWriterResource + Clone>(&mut self, data: Vec<T>) -> usize {
-        for v in data.iter() {
-            self.append::<T>(v.clone());
-        }
-        data.len() * size_of::<T>()
+        data.iter().map(|v| self.append::<T>(v.clone())).sum()
     }
 
     pub fn write_sized_vec<T: ByteWriterResource + Clone>(&mut self, data: Vec<T>) -> usize {
-        self.append::<u32>(data.len() as u32);
-        for v in data.iter() {
-            self.append::<T>(v.clone());
+        self.append::<u32>(data.len() as u32) + self.write_vec(data)
+    }
+
+    /// Writes a vector of T prefixed by its length as an L, returning the bytes written
+    pub fn write_sized_vec_with<L, T>(&mut self, data: Vec<T>) -> Result<usize, Error>
+    where
+        L: ByteWriterResource + TryFrom<usize>,
+        T: ByteWriterResource + Clone,
+    {
+        let len = L::try_from(data.len())
+            .map_err(|_| self.err(WriteErrorKind::LengthOverflow { len: data.len() }))?;
+        Ok(self.append::<L>(len) + self.write_vec(data))
+    }
+
+    /// Appends a UTF-16 string followed by a 0x0000 terminator, returning the bytes written
+    pub fn append_utf16_string(&mut self, data: &str) -> usize {
+        data.encode_utf16()
+            .chain(std::iter::once(0x0000))
+            .map(|unit| self.append::<u16>(unit))
+            .sum()
+    }
+
+    /// Appends a string prefixed by a single length byte, returning the bytes written
+    pub fn append_pascal_string(&mut self, data: &str) -> Result<usize, Error> {
+        let len = u8::try_from(data.len()).map_err(|_| {
+            self.err(WriteErrorKind::StringTooLong {
+                len: data.len(),
+                max: u8::MAX as usize,
+            })
+        })?;
+        self.append::<u8>(len);
+        self.append_vec(data.as_bytes().to_vec());
+        Ok(1 + data.len())
+    }
+
+    /// Appends a string padded with `pad` up to `width` bytes, returning the bytes written
+    ///
+    /// Errors if the string is longer than the field. A string that exactly fills the field
+    /// has no terminator.
+    pub fn append_padded_string(
+        &mut self,
+        data: &str,
+        width: usize,
+        pad: u8,
+    ) -> Result<usize, Error> {
+        if data.len() > width {
+            return Err(self.err(WriteErrorKind::StringTooLong {
+                len: data.len(),
+                max: width,
+            }));
+        }
+        let mut bytes = data.as_bytes().to_vec();
+        bytes.resize(width, pad);
+        self.append_vec(bytes);
+        Ok(width)
+    }
+
+    /// Appends a presence byte followed by the value if there is one, returning the bytes written
+    pub fn append_optional<T: ByteWriterResource>(&mut self, data: Option<T>) -> usize {
+        match data {
+            Some(value) => self.append::<u8>(1) + self.append::<T>(value),
+            None => self.append::<u8>(0),
         }
-        data.len() * size_of::<T>() + 4
     }
 
+    /// Returns a copy of the bytes written so far
+    ///
+    /// This clones the whole buffer, so prefer `as_bytes` to borrow it or `into_inner` to
+    /// take it once writing is done.
     pub fn buf(&self) -> Vec<u8> {
         self.buf.clone()
     }
+
+    /// Returns the bytes written so far without copying them
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Consumes the writer, returning the bytes written
+    ///
+    /// # Examples
+    /// ```
+    /// use bitchomp::{ByteWriter, Endianness};
+    ///
+    /// let mut writer = ByteWriter::new(Endianness::Big);
+    /// writer.append::<u16>(0x0102);
+    /// assert_eq!(writer.as_bytes(), [0x01, 0x02]);
+    /// assert_eq!(writer.into_inner(), [0x01, 0x02]);
+    /// ```
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+
+    /// Returns the bytes written so far, leaving the writer empty to be reused
+    pub fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buf)
+    }
 }
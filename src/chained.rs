@@ -0,0 +1,197 @@
+//! chained.rs
+use std::borrow::Cow;
+
+use super::{ByteReader, ByteReaderResource, Endianness, Error, ErrorKind};
+
+/// A tool for reading from several buffers as if they were one
+///
+/// Values can span the boundaries between segments, in which case their bytes are
+/// copied together to be decoded. Positions are offsets in the combined buffer, and
+/// read errors are wrapped in `InSegment` to say which segment they happened in.
+pub struct ChainedByteReader<'a> {
+    segments: Vec<&'a [u8]>,
+    /// The position of the start of each segment
+    starts: Vec<usize>,
+    position: usize,
+    len: usize,
+    endianness: Endianness,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Returns a ChainedByteReader reading from `segments` in order
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let header = [0x01, 0x02];
+    ///     let payload = [0x03, 0x04];
+    ///     let mut reader = ByteReader::chain(&[&header, &payload], Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read::<u8>()?, 0x01);
+    ///     assert_eq!(reader.read::<u16>()?, 0x0302);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn chain(segments: &[&'a [u8]], endianness: Endianness) -> ChainedByteReader<'a> {
+        let mut starts = Vec::with_capacity(segments.len());
+        let mut len = 0;
+        for segment in segments {
+            starts.push(len);
+            len += segment.len();
+        }
+        ChainedByteReader {
+            segments: segments.to_vec(),
+            starts,
+            position: 0,
+            len,
+            endianness,
+        }
+    }
+}
+
+impl<'a> ChainedByteReader<'a> {
+    /// Returns the length of the remaining buffer
+    pub fn len(&self) -> usize {
+        self.len - self.position
+    }
+
+    /// Returns true if there are no bytes left to read
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the cursor position in the combined buffer
+    pub fn cursor(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the index of the segment holding the byte at `pos`, which is the last
+    /// segment for the end of the buffer
+    pub fn segment_of(&self, pos: usize) -> usize {
+        self.starts
+            .partition_point(|&start| start <= pos)
+            .saturating_sub(1)
+    }
+
+    /// Seeks to a position in the combined buffer
+    ///
+    /// Errors with `SeekOutOfRange` if `pos` is past the end, leaving the cursor untouched.
+    pub fn seek(&mut self, pos: usize) -> Result<(), Error> {
+        if pos > self.len {
+            return Err(Error::new(
+                ErrorKind::SeekOutOfRange {
+                    position: pos as i128,
+                    len: self.len,
+                },
+                Some(self.position),
+            ));
+        }
+        self.position = pos;
+        Ok(())
+    }
+
+    /// Skips `n` bytes, erroring with `UnexpectedEof` without moving if fewer remain
+    pub fn skip_bytes(&mut self, n: usize) -> Result<(), Error> {
+        if self.len() < n {
+            return Err(self.eof_err::<[u8]>(n, self.len()));
+        }
+        self.position += n;
+        Ok(())
+    }
+
+    fn err(&self, kind: ErrorKind) -> Error {
+        self.in_segment(Error::new(kind, Some(self.position)))
+    }
+
+    /// Returns an `UnexpectedEof` error for a T needing `requested` bytes when only
+    /// `available` remain
+    fn eof_err<T: ?Sized>(&self, requested: usize, available: usize) -> Error {
+        self.err(ErrorKind::UnexpectedEof {
+            requested,
+            available,
+            type_name: std::any::type_name::<T>(),
+        })
+    }
+
+    /// Wraps an error in the segment it happened in
+    fn in_segment(&self, error: Error) -> Error {
+        Error {
+            kind: ErrorKind::InSegment {
+                segment: self.segment_of(error.position.unwrap_or(self.position)),
+                error: Box::new(error.kind),
+            },
+            ..error
+        }
+    }
+
+    /// Returns at least `n` contiguous bytes from the cursor, borrowing the rest of the
+    /// current segment if they fit in it and copying from the following segments if not
+    fn contiguous(&self, n: usize) -> Option<Cow<'a, [u8]>> {
+        if self.len() < n {
+            return None;
+        }
+        let index = self.segment_of(self.position);
+        let rest = &self.segments[index][self.position - self.starts[index]..];
+        if rest.len() >= n {
+            return Some(Cow::Borrowed(rest));
+        }
+        let mut bytes = rest.to_vec();
+        for segment in &self.segments[index + 1..] {
+            if bytes.len() >= n {
+                break;
+            }
+            bytes.extend_from_slice(segment);
+        }
+        Some(Cow::Owned(bytes))
+    }
+
+    /// Reads a type T from the buffer
+    pub fn read<T: for<'b> ByteReaderResource<'b>>(&mut self) -> Result<T, Error> {
+        Ok(self.read_n(1)?.remove(0))
+    }
+
+    /// Reads a type T from the buffer without consuming
+    pub fn peek<T: for<'b> ByteReaderResource<'b>>(&self) -> Result<T, Error> {
+        Ok(self.decode(1)?.0.remove(0))
+    }
+
+    /// Reads a type T from the buffer n times
+    pub fn read_n<T: for<'b> ByteReaderResource<'b>>(&mut self, n: usize) -> Result<Vec<T>, Error> {
+        let (values, size) = self.decode(n)?;
+        self.position += size;
+        Ok(values)
+    }
+
+    /// Decodes n values of type T from the cursor, returning them and their total size
+    fn decode<T: for<'b> ByteReaderResource<'b>>(
+        &self,
+        n: usize,
+    ) -> Result<(Vec<T>, usize), Error> {
+        let size = if T::VARIABLE_SIZE {
+            0
+        } else {
+            T::SIZE.saturating_mul(n)
+        };
+        let bytes = self
+            .contiguous(size)
+            .ok_or_else(|| self.eof_err::<T>(size, self.len()))?;
+        let decode = |bytes: &[u8]| {
+            let mut reader = ByteReader::with_origin(bytes, self.endianness, self.position);
+            let chomps = reader.read_n::<T>(n)?;
+            Ok::<_, Error>((chomps.iter().map(|c| c.inner()).collect(), reader.cursor()))
+        };
+        match decode(&bytes) {
+            // variable size values may carry on into the next segments
+            Err(e) if e.kind.is_eof() && T::VARIABLE_SIZE && bytes.len() < self.len() => {
+                decode(&self.contiguous(self.len()).unwrap_or_default())
+            }
+            result => result,
+        }
+        .map_err(|e| self.in_segment(e))
+    }
+}
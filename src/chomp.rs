@@ -1,21 +1,310 @@
-#[derive(Clone, Copy)]
-pub struct Chomp<T>(pub(crate) *const T);
+use std::{cmp::Ordering, fmt, marker::PhantomData};
 
-impl<T> Chomp<T> {
-    pub fn new(value: &T) -> Self {
-        Self(value as *const _)
+use super::{Endianness, TryFromBytes};
+
+// any type that can be decoded from a slice of bytes of any lifetime
+pub trait ChompResource = TryFromBytes<Bytes: for<'b> From<&'b [u8]>>;
+
+/// A value in the buffer, along with the endianness it's stored in
+///
+/// The value is decoded each time it's used rather than stored, so there's no `&T` to
+/// deref to. Comparisons and formatting go through the decoded value instead:
+/// ```
+/// #![feature(generic_const_exprs)]
+///
+/// use bitchomp::{ByteReader, Endianness, Error};
+///
+/// fn main() -> Result<(), Error> {
+///     let buf = [0x02, 0x00, 0x01, 0x00];
+///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+///
+///     let mut values = reader.read_n::<u16>(2)?;
+///     values.sort();
+///     assert_eq!(values, [1, 2]);
+///     assert_eq!(format!("{values:?}"), "[1, 2]");
+///
+///     Ok(())
+/// }
+/// ```
+///
+/// A Chomp borrows the buffer it was read from, so it can't outlive it:
+/// ```compile_fail
+/// #![feature(generic_const_exprs)]
+///
+/// use bitchomp::{ByteReader, Endianness};
+///
+/// let buf = vec![1, 0, 0, 0];
+/// let value = ByteReader::new(&buf, Endianness::Little).read::<u32>().unwrap();
+/// drop(buf);
+/// value.inner();
+/// ```
+pub struct Chomp<'a, T>(
+    pub(crate) &'a [u8],
+    pub(crate) Endianness,
+    pub(crate) PhantomData<T>,
+);
+
+// derived impls would needlessly require T: Clone
+impl<'a, T> Clone for Chomp<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Chomp<'a, T> {}
+
+impl<'a, T> Chomp<'a, T> {
+    /// Wraps the bytes of `value` as they're laid out in memory, in native endianness
+    ///
+    /// Decoding only gives back `value` if its layout in memory is also its encoding, as
+    /// for the primitive integers and floats. Otherwise `inner` may panic.
+    ///
+    /// # Safety
+    ///
+    /// Every byte of `value` must be initialised, so T can't contain padding. That rules
+    /// out many tuples, structs and enums, like `(u8, u32)` or `Option<u32>`.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::Chomp;
+    ///
+    /// let value = 0x01020304u32;
+    /// // SAFETY: u32 has no padding
+    /// let chomp = unsafe { Chomp::new(&value) };
+    /// assert_eq!(chomp.size(), 4);
+    /// assert_eq!(chomp.inner(), value);
+    /// ```
+    pub unsafe fn new(value: &'a T) -> Self {
+        // SAFETY: the bytes are borrowed for as long as value, and the caller guarantees
+        // they're all initialised
+        let bytes =
+            unsafe { std::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) };
+        Self(bytes, Endianness::native(), PhantomData)
+    }
+
+    pub(crate) fn with_endianness(bytes: &'a [u8], endianness: Endianness) -> Self {
+        Self(bytes, endianness, PhantomData)
+    }
+
+    /// Returns the number of bytes the value occupies in the buffer
+    pub fn size(&self) -> usize {
+        self.0.len()
     }
+
+    /// Returns the bytes the value occupies in the buffer
+    pub fn bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'a, T: ChompResource> Chomp<'a, T> {
     pub fn inner(&self) -> T {
-        unsafe { std::ptr::read_unaligned(self.0) }
+        match T::try_from_slice(self.0, self.1) {
+            Ok((value, _)) => value,
+            Err(_) => unreachable!("chomped bytes are validated when read"),
+        }
     }
 }
 
+impl<'a, T: ChompResource + fmt::Debug> fmt::Debug for Chomp<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner(), f)
+    }
+}
+
+impl<'a, T: ChompResource + fmt::Display> fmt::Display for Chomp<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner(), f)
+    }
+}
+
+impl<'a, T: ChompResource + PartialEq> PartialEq<T> for Chomp<'a, T> {
+    fn eq(&self, other: &T) -> bool {
+        self.inner() == *other
+    }
+}
+
+impl<'a, 'b, T: ChompResource + PartialEq> PartialEq<Chomp<'b, T>> for Chomp<'a, T> {
+    fn eq(&self, other: &Chomp<'b, T>) -> bool {
+        self.inner() == other.inner()
+    }
+}
+
+impl<'a, T: ChompResource + Eq> Eq for Chomp<'a, T> {}
+
+impl<'a, T: ChompResource + PartialOrd> PartialOrd<T> for Chomp<'a, T> {
+    fn partial_cmp(&self, other: &T) -> Option<Ordering> {
+        self.inner().partial_cmp(other)
+    }
+}
+
+impl<'a, 'b, T: ChompResource + PartialOrd> PartialOrd<Chomp<'b, T>> for Chomp<'a, T> {
+    fn partial_cmp(&self, other: &Chomp<'b, T>) -> Option<Ordering> {
+        self.inner().partial_cmp(&other.inner())
+    }
+}
+
+impl<'a, T: ChompResource + Ord> Ord for Chomp<'a, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.inner().cmp(&other.inner())
+    }
+}
+
+/// A run of fixed size values in the buffer, decoded as they're accessed
+///
+/// Unlike `Vec<Chomp<T>>` this doesn't allocate, so prefer it for large tables.
+pub struct ChompSlice<'a, T> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) len: usize,
+    pub(crate) endianness: Endianness,
+    pub(crate) resource_type: PhantomData<T>,
+}
+
+impl<'a, T> Clone for ChompSlice<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for ChompSlice<'a, T> {}
+
+impl<'a, T: ChompResource> ChompSlice<'a, T> {
+    /// Returns the number of values
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value at `index`, or None if it's out of bounds
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        let bytes = &self.bytes[index * T::SIZE..];
+        match T::try_from_slice(&bytes[..T::SIZE], self.endianness) {
+            Ok((value, _)) => Some(value),
+            Err(_) => unreachable!("chomped bytes are validated when read"),
+        }
+    }
+
+    /// Returns an iterator decoding each value as it's reached
+    pub fn iter(&self) -> ChompSliceIter<'a, T> {
+        ChompSliceIter {
+            slice: *self,
+            index: 0,
+        }
+    }
+
+    /// Decodes every value into a Vec
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+
+    /// Returns the bytes the values occupy in the buffer
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl<'a, T: ChompResource> IntoIterator for ChompSlice<'a, T> {
+    type Item = T;
+    type IntoIter = ChompSliceIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: ChompResource + fmt::Debug> fmt::Debug for ChompSlice<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the values of a ChompSlice
+pub struct ChompSliceIter<'a, T> {
+    slice: ChompSlice<'a, T>,
+    index: usize,
+}
+
+impl<'a, T: ChompResource> Iterator for ChompSliceIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.slice.get(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.slice.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: ChompResource> ExactSizeIterator for ChompSliceIter<'a, T> {}
+
+/// Decodes a run of Chomps into their values
+///
+/// `IntoIterator` can't be implemented for `Vec<Chomp<T>>` outside of std, so use
+/// `iter_values` to decode lazily:
+/// ```
+/// #![feature(generic_const_exprs)]
+///
+/// use bitchomp::{ByteReader, ChompFlatten, Endianness, Error};
+///
+/// fn main() -> Result<(), Error> {
+///     let buf = [0x01, 0x00, 0x02, 0x00, 0x03, 0x00];
+///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+///
+///     let values = reader.read_n::<u16>(3)?;
+///     assert_eq!(values.iter_values().sum::<u16>(), 6);
+///     assert_eq!(values.into_flattened(), [1, 2, 3]);
+///
+///     Ok(())
+/// }
+/// ```
 pub trait ChompFlatten<T> {
+    /// Decodes every value into a Vec
     fn flatten(&self) -> Vec<T>;
+
+    /// Decodes every value into a Vec, consuming the Chomps
+    fn into_flattened(self) -> Vec<T>
+    where
+        Self: Sized,
+    {
+        self.flatten()
+    }
+
+    /// Returns an iterator decoding each value as it's reached
+    fn iter_values(&self) -> impl Iterator<Item = T> + '_;
 }
 
-impl<T> ChompFlatten<T> for Vec<Chomp<T>> {
+impl<'a, T: ChompResource> ChompFlatten<T> for [Chomp<'a, T>] {
     fn flatten(&self) -> Vec<T> {
-        self.iter().map(|c| c.inner()).collect()
+        self.iter_values().collect()
+    }
+
+    fn iter_values(&self) -> impl Iterator<Item = T> + '_ {
+        self.iter().map(Chomp::inner)
+    }
+}
+
+impl<'a, T: ChompResource> ChompFlatten<T> for Vec<Chomp<'a, T>> {
+    fn flatten(&self) -> Vec<T> {
+        self.as_slice().flatten()
+    }
+
+    fn into_flattened(self) -> Vec<T> {
+        self.into_iter().map(|c| c.inner()).collect()
+    }
+
+    fn iter_values(&self) -> impl Iterator<Item = T> + '_ {
+        self.as_slice().iter_values()
     }
 }
@@ -0,0 +1,143 @@
+//! encoding.rs
+use std::io::BufRead;
+
+use encoding_rs::{DecoderResult, EncoderResult, Encoding};
+
+use super::{ByteReader, ByteWriter, Error, ErrorKind, WriteErrorKind};
+
+/// Whether strings in `encoding` can end in a single NUL byte, which rules out UTF-16
+/// and `replacement`, whose encoders also write UTF-8 rather than the encoding itself
+fn nul_terminable(encoding: &'static Encoding) -> bool {
+    encoding.is_ascii_compatible() && encoding.output_encoding() == encoding
+}
+
+impl<'a> ByteReader<'a> {
+    /// Reads a NUL-terminated string stored in `encoding`, consuming the terminator
+    ///
+    /// Errors with `UnterminatedString` if there's no terminator and `InvalidEncoding`
+    /// with the position of the first malformed byte, leaving the cursor untouched.
+    /// Encodings which aren't ASCII compatible, such as UTF-16, error with
+    /// `UnsupportedEncoding`.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x63, 0x61, 0x66, 0xE9, 0x00];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let value = reader.read_string_encoded(encoding_rs::WINDOWS_1252)?;
+    ///     assert_eq!(value, "café");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_string_encoded(&mut self, encoding: &'static Encoding) -> Result<String, Error> {
+        self.check_encoding(encoding)?;
+        let len = self.cursor.iter().position(|&b| b == 0x00).ok_or_else(|| {
+            self.err(ErrorKind::UnterminatedString {
+                scanned: self.len(),
+            })
+        })?;
+        let value = self.decode(encoding, len)?;
+        self.consume(len + 1);
+        Ok(value)
+    }
+
+    /// Reads a string stored in `encoding` in a fixed width field, always consuming `width` bytes
+    ///
+    /// The string ends at the first NUL in the field, or at the end of the field if there is none.
+    /// Errors with `UnsupportedEncoding` in the same way as `read_string_encoded`.
+    pub fn read_string_encoded_fixed(
+        &mut self,
+        encoding: &'static Encoding,
+        width: usize,
+    ) -> Result<String, Error> {
+        self.check_encoding(encoding)?;
+        if self.len() < width {
+            return Err(self.eof_err::<String>(width, self.len()));
+        }
+        let len = self.cursor[..width]
+            .iter()
+            .position(|&b| b == 0x00)
+            .unwrap_or(width);
+        let value = self.decode(encoding, len)?;
+        self.consume(width);
+        Ok(value)
+    }
+
+    /// Errors with `UnsupportedEncoding` unless strings in `encoding` can be NUL-terminated
+    fn check_encoding(&self, encoding: &'static Encoding) -> Result<(), Error> {
+        if !nul_terminable(encoding) {
+            return Err(self.err(ErrorKind::UnsupportedEncoding {
+                encoding: encoding.name(),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Decodes the next `len` bytes without consuming them
+    fn decode(&self, encoding: &'static Encoding, len: usize) -> Result<String, Error> {
+        let bytes = &self.cursor[..len];
+        let mut decoder = encoding.new_decoder_without_bom_handling();
+        let capacity = decoder
+            .max_utf8_buffer_length_without_replacement(len)
+            .unwrap_or(len * 3);
+        let mut value = String::with_capacity(capacity);
+        let (result, read) = decoder.decode_to_string_without_replacement(bytes, &mut value, true);
+        match result {
+            DecoderResult::InputEmpty => Ok(value),
+            DecoderResult::Malformed(bad, after) => Err(self.err(ErrorKind::InvalidEncoding {
+                encoding: encoding.name(),
+                offset: self.absolute_cursor() + read - after as usize - bad as usize,
+            })),
+            DecoderResult::OutputFull => {
+                unreachable!("the output was allocated for the worst case")
+            }
+        }
+    }
+}
+
+impl ByteWriter {
+    /// Appends a string in `encoding` followed by a NUL terminator, returning the bytes written
+    ///
+    /// Errors with the first character which `encoding` can't represent, and with
+    /// `UnsupportedEncoding` for encodings which aren't ASCII compatible, such as UTF-16.
+    pub fn append_string_encoded(
+        &mut self,
+        data: &str,
+        encoding: &'static Encoding,
+    ) -> Result<usize, Error> {
+        if !nul_terminable(encoding) {
+            return Err(self.err(WriteErrorKind::UnsupportedEncoding {
+                encoding: encoding.name(),
+            }));
+        }
+        let mut encoder = encoding.new_encoder();
+        let capacity = encoder
+            .max_buffer_length_from_utf8_without_replacement(data.len())
+            .unwrap_or(data.len() * 4);
+        let mut bytes = Vec::with_capacity(capacity + 1);
+        let (result, _) =
+            encoder.encode_from_utf8_to_vec_without_replacement(data, &mut bytes, true);
+        match result {
+            EncoderResult::InputEmpty => {}
+            EncoderResult::Unmappable(ch) => {
+                return Err(self.err(WriteErrorKind::UnmappableChar {
+                    ch,
+                    encoding: encoding.name(),
+                }))
+            }
+            EncoderResult::OutputFull => {
+                unreachable!("the output was allocated for the worst case")
+            }
+        }
+        bytes.push(0x00);
+        let size = bytes.len();
+        self.append_vec(bytes);
+        Ok(size)
+    }
+}
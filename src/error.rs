@@ -0,0 +1,733 @@
+//! error.rs
+#[cfg(feature = "backtrace")]
+use std::backtrace::Backtrace;
+use std::{convert::Infallible, fmt, io, string::FromUtf8Error};
+
+use super::{bytereader::hex_dump, TryFromBytesError, TryFromIntError, WriteErrorKind};
+
+/// Error returned by everything in bitchomp
+///
+/// Reading, writing, decoding and io all fail with this one type, so `?` works across them.
+/// Conversions from io and UTF-8 errors mean `?` works on those too:
+/// ```
+/// #![feature(generic_const_exprs)]
+///
+/// use bitchomp::{ByteReader, Endianness, Error};
+///
+/// fn name(path: &str) -> Result<String, Error> {
+///     let buf = std::fs::read(path)?;
+///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+///     let len = reader.read_value::<u8>()? as usize;
+///     Ok(String::from_utf8(reader.read_slice(len)?.to_vec())?)
+/// }
+///
+/// assert!(name("/does/not/exist").is_err());
+/// ```
+pub struct Error {
+    pub(crate) kind: ErrorKind,
+    pub(crate) position: Option<usize>,
+    /// Everything else, boxed to keep results small
+    pub(crate) details: Option<Box<Details>>,
+}
+
+/// What's known about an error beyond its kind and position
+pub(crate) struct Details {
+    /// Labels added by `with_context`, innermost first
+    pub(crate) context: Vec<String>,
+    /// The bytes around the error, if the reader captures them
+    pub(crate) snippet: Option<Snippet>,
+    /// Where the error was made, if `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` enable it
+    #[cfg(feature = "backtrace")]
+    pub(crate) backtrace: Backtrace,
+}
+
+impl Details {
+    fn new() -> Self {
+        Details {
+            context: Vec::new(),
+            snippet: None,
+            #[cfg(feature = "backtrace")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+}
+
+/// A window of the buffer around an error, captured by `ByteReader::with_error_snippets`
+pub(crate) struct Snippet {
+    pub(crate) bytes: Vec<u8>,
+    /// The absolute position of the first byte
+    pub(crate) start: usize,
+}
+
+impl Error {
+    /// Creates an error of `kind` at absolute position `position`, for libraries layered on
+    /// bitchomp to report errors in the same form
+    pub fn new(kind: ErrorKind, position: Option<usize>) -> Self {
+        Error {
+            kind,
+            position,
+            // the backtrace has to be captured here, whereas the rest is added later
+            details: cfg!(feature = "backtrace").then(|| Box::new(Details::new())),
+        }
+    }
+
+    /// Creates an `UnexpectedEof` error at absolute position `position`, for a read needing
+    /// `requested` bytes when only `available` remain
+    ///
+    /// The kind's `type_name` is empty, as the error isn't for a particular type.
+    pub fn eof(position: usize, requested: usize, available: usize) -> Self {
+        let kind = ErrorKind::UnexpectedEof {
+            requested,
+            available,
+            type_name: "",
+        };
+        Error::new(kind, Some(position))
+    }
+
+    /// Creates an error for bytes which are invalid in a way the other kinds don't cover,
+    /// displayed as `message`
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error, Result};
+    ///
+    /// fn version(reader: &mut ByteReader) -> Result<u8> {
+    ///     match reader.read_value::<u8>()? {
+    ///         version @ 1..=3 => Ok(version),
+    ///         version => Err(Error::invalid_data(format!("unsupported version {version}"))),
+    ///     }
+    /// }
+    ///
+    /// let mut reader = ByteReader::new(&[7], Endianness::Little);
+    /// assert_eq!(version(&mut reader).unwrap_err().to_string(), "unsupported version 7");
+    /// ```
+    pub fn invalid_data(message: impl fmt::Display) -> Self {
+        ErrorKind::InvalidData(message.to_string()).into()
+    }
+
+    /// Creates an error for anything else, displayed as `message`
+    pub fn custom(message: impl fmt::Display) -> Self {
+        ErrorKind::Custom(message.to_string()).into()
+    }
+
+    pub(crate) fn details_mut(&mut self) -> &mut Details {
+        self.details.get_or_insert_with(|| Box::new(Details::new()))
+    }
+
+    pub(crate) fn snippet(&self) -> Option<&Snippet> {
+        self.details.as_ref()?.snippet.as_ref()
+    }
+
+    /// Returns the backtrace of where the error was made
+    ///
+    /// Backtraces are only captured if `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` enable them,
+    /// otherwise its status is `Disabled`.
+    #[cfg(feature = "backtrace")]
+    pub fn backtrace(&self) -> &Backtrace {
+        match &self.details {
+            Some(details) => &details.backtrace,
+            None => unreachable!("details are always made with the backtrace feature"),
+        }
+    }
+
+    /// Labels what was being read when the error occurred, as it propagates outwards
+    ///
+    /// Labels are displayed outermost first, before the error itself. The label is only
+    /// formatted here, so passing `format_args!` costs nothing until there's an error.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness};
+    ///
+    /// let buf = [0x01, 0x02];
+    /// let err = ByteReader::new(&buf, Endianness::Little)
+    ///     .read_value::<u32>()
+    ///     .map_err(|e| e.with_context("header"))
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "while reading header: u32 needs 4 bytes but only 2 remain at 0x0"
+    /// );
+    /// ```
+    pub fn with_context(mut self, label: impl fmt::Display) -> Self {
+        self.details_mut().context.push(label.to_string());
+        self
+    }
+
+    /// Returns the labels added by `with_context`, outermost first
+    pub fn context_labels(&self) -> impl Iterator<Item = &str> {
+        let context = self.details.as_ref().map_or(&[][..], |d| &d.context[..]);
+        context.iter().rev().map(String::as_str)
+    }
+
+    /// Returns what went wrong
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness};
+    ///
+    /// let buf = [0x01, 0x02];
+    /// let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    /// // fall back to a u16 if the buffer is too short for a u32
+    /// let value = match reader.read_value::<u32>() {
+    ///     Ok(value) => value,
+    ///     Err(e) if e.kind().is_eof() => {
+    ///         reader.read_value::<u16>().unwrap() as u32
+    ///     }
+    ///     Err(e) => panic!("{e}"),
+    /// };
+    /// assert_eq!(value, 0x0201);
+    /// ```
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Returns the absolute position in the buffer the error occurred at, if it's known
+    ///
+    /// Conversions from io and decoding errors have no position, as they happen outside of
+    /// a buffer.
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+
+    /// Returns what went wrong, consuming the error
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the stable code of the error, see `ErrorCode`
+    pub fn code(&self) -> ErrorCode {
+        self.kind.code()
+    }
+
+    /// Whether the error is from running out of bytes, as opposed to the bytes being invalid
+    pub fn is_eof(&self) -> bool {
+        self.code().is_eof()
+    }
+}
+
+impl std::fmt::Debug for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        #[cfg(feature = "backtrace")]
+        let alternate = f.alternate();
+        let mut debug = f.debug_struct("Error");
+        debug
+            .field("kind", &self.kind)
+            .field_with("position", |f| match self.position {
+                Some(position) => write!(f, "Some({position:#x})"),
+                None => write!(f, "None"),
+            })
+            .field("context", &self.context_labels().collect::<Vec<_>>());
+        // backtraces are long, so only show them when asked for with {:#?}
+        #[cfg(feature = "backtrace")]
+        if alternate {
+            debug.field("backtrace", self.backtrace());
+        }
+        debug.finish()
+    }
+}
+
+/// What went wrong, returned by `Error::kind`
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A `type_name` needed `requested` bytes but only `available` remained. The type name
+    /// is empty for errors made by `Error::eof`
+    UnexpectedEof {
+        requested: usize,
+        available: usize,
+        type_name: &'static str,
+    },
+    /// The buffer ended after `scanned` bytes without a NUL terminator
+    UnterminatedString { scanned: usize },
+    /// The buffer ended without containing `delimiter`
+    NoDelimiter { delimiter: u8 },
+    /// A string's bytes aren't valid UTF-8 from absolute `offset`, with the bytes scanned
+    /// for the string in `error`
+    InvalidUtf8 { offset: usize, error: FromUtf8Error },
+    /// The bytes at `offset` aren't valid in `encoding`
+    InvalidEncoding {
+        encoding: &'static str,
+        offset: usize,
+    },
+    /// Strings in `encoding` can't be NUL-terminated, as it isn't ASCII compatible
+    UnsupportedEncoding { encoding: &'static str },
+    /// A timestamp is too far from the unix epoch to be a SystemTime
+    TimestampOutOfRange,
+    /// The packed BCD byte at `offset` has a `nibble` above 9
+    InvalidBcd { offset: usize, nibble: u8 },
+    /// A packed BCD number is too large for a u64
+    BcdOverflow,
+    /// A length prefixed block was parsed with `remaining` bytes left unread
+    UnconsumedBytes { remaining: usize },
+    /// The buffer ended after `read` values without the sentinel value
+    NoSentinel { read: usize },
+    /// A seek to `position` is outside of the buffer of length `len`
+    SeekOutOfRange { position: i128, len: usize },
+    /// A split at `offset` is past the `remaining` bytes
+    SplitOutOfRange { offset: usize, remaining: usize },
+    /// A mark at absolute `position` is outside of the buffer since the reader was rebased
+    /// to `origin`
+    StaleMark { position: usize, origin: usize },
+    /// The magic bytes were `actual` rather than `expected`
+    BadMagic { expected: Vec<u8>, actual: Vec<u8> },
+    /// The buffer ended without containing the searched for pattern
+    NoPattern,
+    /// A lookahead asked for `overrun` bytes past the end of the buffer
+    PastEnd { overrun: usize },
+    /// `pop_pos` was called with no saved positions
+    EmptyPositionStack,
+    /// Aligning to `alignment` bytes from `origin` is impossible, as the alignment is 0 or
+    /// the origin is past the cursor
+    InvalidAlignment { origin: usize, alignment: usize },
+    /// An error reading from segment `segment` of a ChainedByteReader
+    InSegment {
+        segment: usize,
+        error: Box<ErrorKind>,
+    },
+    /// A ChompSlice can't be made over a variable size type, as its values can't be indexed
+    VariableSize,
+    /// A length prefix declared `declared` values, more than fit in the `remaining` bytes,
+    /// or for the streaming readers in the most bytes they'll buffer
+    DeclaredSizeTooLarge { declared: usize, remaining: usize },
+    /// A length prefix doesn't fit in a usize
+    LengthOverflow,
+    /// A variable length integer is too long or too large for its type
+    VarintOverflow,
+    /// There weren't enough bits left to read from bit position `bit`
+    NoBits { bit: usize },
+    /// A value couldn't be written
+    Write(WriteErrorKind),
+    /// The bytes couldn't be decoded as a value
+    Decode(TryFromBytesError),
+    /// The underlying source or sink failed
+    Io(io::Error),
+    /// The bytes are invalid in a way described by the message, see `Error::invalid_data`
+    InvalidData(String),
+    /// Any other error, described by the message, see `Error::custom`
+    Custom(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let labels = self.context_labels().collect::<Vec<_>>();
+        if !labels.is_empty() {
+            write!(f, "while reading {}: ", labels.join(" "))?;
+        }
+        match self.position {
+            Some(position) => write!(f, "{} at {position:#x}", self.kind)?,
+            None => write!(f, "{}", self.kind)?,
+        }
+        if let (Some(snippet), Some(position)) = (self.snippet(), self.position) {
+            let pos = position - snippet.start;
+            let dump = hex_dump(&snippet.bytes, snippet.start, pos, pos, snippet.bytes.len());
+            write!(f, "\n{}", dump.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        let mut kind = &self.kind;
+        while let ErrorKind::InSegment { error, .. } = kind {
+            kind = error;
+        }
+        match kind {
+            ErrorKind::InvalidUtf8 { error, .. } => Some(error),
+            ErrorKind::Decode(err) => Some(err),
+            ErrorKind::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ErrorKind::*;
+        match self {
+            UnexpectedEof {
+                requested,
+                available,
+                type_name: "",
+            } => write!(f, "{requested} bytes were requested but {available} remain"),
+            UnexpectedEof {
+                requested,
+                available,
+                type_name,
+            } => write!(
+                f,
+                "{type_name} needs {requested} bytes but only {available} remain"
+            ),
+            UnterminatedString { scanned } => {
+                write!(f, "no NUL terminator in the {scanned} bytes left")
+            }
+            NoDelimiter { delimiter } => write!(f, "no {delimiter:#04x} delimiter found"),
+            InvalidUtf8 { offset, .. } => {
+                write!(f, "string isn't valid UTF-8 from {offset:#x}")
+            }
+            InvalidEncoding { encoding, offset } => {
+                write!(f, "bytes at {offset:#x} aren't valid {encoding}")
+            }
+            UnsupportedEncoding { encoding } => {
+                write!(f, "{encoding} strings can't be NUL-terminated")
+            }
+            TimestampOutOfRange => write!(f, "timestamp is out of range of SystemTime"),
+            InvalidBcd { offset, nibble } => {
+                write!(f, "BCD byte at {offset:#x} has the nibble {nibble:#x}")
+            }
+            BcdOverflow => write!(f, "BCD number is too large for a u64"),
+            UnconsumedBytes { remaining } => {
+                write!(f, "{remaining} bytes of the block were left unread")
+            }
+            NoSentinel { read } => write!(f, "no sentinel after reading {read} values"),
+            SeekOutOfRange { position, .. } if *position < 0 => {
+                write!(
+                    f,
+                    "seek to -{:#x} is before the start of the buffer",
+                    position.abs()
+                )
+            }
+            SeekOutOfRange { position, len } => {
+                write!(
+                    f,
+                    "seek to {position:#x} is past the end of the buffer of length {len:#x}"
+                )
+            }
+            SplitOutOfRange { offset, remaining } => {
+                write!(f, "split at {offset} is past the {remaining} bytes left")
+            }
+            StaleMark { position, origin } => write!(
+                f,
+                "mark at {position:#x} is before the reader's origin at {origin:#x}"
+            ),
+            BadMagic { expected, actual } => {
+                write!(f, "bad magic {actual:02x?}, expected {expected:02x?}")
+            }
+            NoPattern => write!(f, "pattern not found"),
+            PastEnd { overrun } => write!(f, "lookahead is {overrun} bytes past the end"),
+            EmptyPositionStack => write!(f, "no saved positions to pop"),
+            InvalidAlignment { origin, alignment } => {
+                write!(f, "can't align to {alignment} bytes from {origin:#x}")
+            }
+            InSegment { segment, error } => write!(f, "{error} in segment {segment}"),
+            VariableSize => write!(f, "can't view a variable size type as a slice"),
+            DeclaredSizeTooLarge {
+                declared,
+                remaining,
+            } => write!(
+                f,
+                "declared length of {declared} values doesn't fit in the {remaining} bytes left"
+            ),
+            LengthOverflow => write!(f, "length prefix doesn't fit in a usize"),
+            VarintOverflow => write!(f, "variable length integer is too long"),
+            NoBits { bit } => write!(f, "not enough bits left at bit {bit}"),
+            Write(err) => write!(f, "{err}"),
+            Decode(_) => write!(f, "failed to convert bytes"),
+            Io(_) => write!(f, "failed to read from the source"),
+            InvalidData(message) | Custom(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl ErrorKind {
+    /// Whether the error is from running out of bytes, as opposed to the bytes being invalid
+    pub fn is_eof(&self) -> bool {
+        self.code().is_eof()
+    }
+
+    /// Returns the stable code of the kind, looking through the segment, field or element
+    /// it happened in
+    pub fn code(&self) -> ErrorCode {
+        use ErrorKind::*;
+        match self {
+            UnexpectedEof { .. } => ErrorCode::UnexpectedEof,
+            UnterminatedString { .. } => ErrorCode::UnterminatedString,
+            NoDelimiter { .. } => ErrorCode::NoDelimiter,
+            InvalidUtf8 { .. } => ErrorCode::InvalidUtf8,
+            InvalidEncoding { .. } => ErrorCode::InvalidEncoding,
+            UnsupportedEncoding { .. } => ErrorCode::UnsupportedEncoding,
+            TimestampOutOfRange => ErrorCode::TimestampOutOfRange,
+            InvalidBcd { .. } => ErrorCode::InvalidBcd,
+            BcdOverflow => ErrorCode::BcdOverflow,
+            UnconsumedBytes { .. } => ErrorCode::UnconsumedBytes,
+            NoSentinel { .. } => ErrorCode::NoSentinel,
+            SeekOutOfRange { .. } => ErrorCode::SeekOutOfRange,
+            SplitOutOfRange { .. } => ErrorCode::SplitOutOfRange,
+            StaleMark { .. } => ErrorCode::StaleMark,
+            BadMagic { .. } => ErrorCode::BadMagic,
+            NoPattern => ErrorCode::NoPattern,
+            PastEnd { .. } => ErrorCode::PastEnd,
+            EmptyPositionStack => ErrorCode::EmptyPositionStack,
+            InvalidAlignment { .. } => ErrorCode::InvalidAlignment,
+            InSegment { error, .. } => error.code(),
+            VariableSize => ErrorCode::VariableSize,
+            DeclaredSizeTooLarge { .. } => ErrorCode::DeclaredSizeTooLarge,
+            LengthOverflow => ErrorCode::LengthOverflow,
+            VarintOverflow => ErrorCode::VarintOverflow,
+            NoBits { .. } => ErrorCode::NoBits,
+            Write(kind) => kind.code(),
+            Decode(err) => err.code(),
+            Io(_) => ErrorCode::Io,
+            InvalidData(_) => ErrorCode::InvalidData,
+            Custom(_) => ErrorCode::Custom,
+        }
+    }
+}
+
+/// A stable identity for each kind of error, returned by `Error::code`
+///
+/// Unlike `ErrorKind` these hold no data, and the discriminants never change, so they can
+/// be passed across a plugin or FFI boundary with `as u16`. New codes may be added, so
+/// matches need a wildcard arm:
+/// ```
+/// #![feature(generic_const_exprs)]
+///
+/// use bitchomp::{ByteReader, Endianness, ErrorCode};
+///
+/// let buf = [0xFF, 0x00];
+/// let mut reader = ByteReader::new(&buf, Endianness::Little);
+///
+/// let status = match reader.read_value::<String>() {
+///     Ok(_) => 0,
+///     Err(e) => match e.code() {
+///         ErrorCode::UnexpectedEof => 1,
+///         ErrorCode::InvalidUtf8 => 2,
+///         code => code as u16,
+///     },
+/// };
+/// assert_eq!(status, ErrorCode::InvalidUtf8 as u16);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[repr(u16)]
+pub enum ErrorCode {
+    UnexpectedEof = 1,
+    InvalidUtf8 = 2,
+    // 3 and 4 were the NoBytes and NotEnoughBytes kinds, which are now UnexpectedEof
+    UnterminatedString = 5,
+    NoDelimiter = 6,
+    /// Reading or decoding found non-zero padding
+    NonZeroPadding = 7,
+    InvalidEncoding = 8,
+    /// Reading or writing a timestamp out of range
+    TimestampOutOfRange = 9,
+    InvalidBcd = 10,
+    /// Reading or writing BCD too large for its type or length
+    BcdOverflow = 11,
+    UnconsumedBytes = 12,
+    NoSentinel = 13,
+    SeekOutOfRange = 14,
+    SplitOutOfRange = 15,
+    StaleMark = 16,
+    BadMagic = 17,
+    NoPattern = 18,
+    PastEnd = 19,
+    EmptyPositionStack = 20,
+    VariableSize = 21,
+    DeclaredSizeTooLarge = 22,
+    /// Reading or writing a length that doesn't fit its type
+    LengthOverflow = 23,
+    /// Reading or writing a variable length integer that doesn't fit its encoding
+    VarintOverflow = 24,
+    NoBits = 25,
+    InvalidUtf16 = 26,
+    ArrayFromSlice = 27,
+    OutOfBounds = 28,
+    InvalidBool = 29,
+    InvalidChar = 30,
+    ZeroValue = 31,
+    InvalidDiscriminant = 32,
+    Io = 33,
+    WriteFailed = 34,
+    StringTooLong = 35,
+    UnmappableChar = 36,
+    /// A TryFromBytes impl failed with its own error, or an error from `Error::custom`
+    Custom = 37,
+    WriteOutOfRange = 38,
+    InvalidData = 39,
+    InvalidAlignment = 40,
+    /// Reading or writing a NUL-terminated string in an encoding that isn't ASCII compatible
+    UnsupportedEncoding = 41,
+}
+
+impl ErrorCode {
+    /// Whether the code is from running out of bytes, as opposed to the bytes being invalid
+    pub fn is_eof(self) -> bool {
+        matches!(self, ErrorCode::UnexpectedEof | ErrorCode::PastEnd)
+    }
+}
+
+impl TryFromBytesError {
+    /// Returns the stable code of the error, looking through the field or element it
+    /// happened in
+    pub fn code(&self) -> ErrorCode {
+        use TryFromBytesError::*;
+        match self {
+            StringFromBytes(_) => ErrorCode::InvalidUtf8,
+            StringFromUtf16Bytes(_) => ErrorCode::InvalidUtf16,
+            ArrayFromSlice => ErrorCode::ArrayFromSlice,
+            OutOfBounds => ErrorCode::OutOfBounds,
+            InvalidBool(_) => ErrorCode::InvalidBool,
+            InvalidChar(_) => ErrorCode::InvalidChar,
+            ZeroValue => ErrorCode::ZeroValue,
+            NonZeroPadding { .. } => ErrorCode::NonZeroPadding,
+            Field { error, .. } | Element { error, .. } => error.code(),
+            InvalidDiscriminant { .. } => ErrorCode::InvalidDiscriminant,
+            Custom(_) => ErrorCode::Custom,
+        }
+    }
+}
+
+impl WriteErrorKind {
+    /// Returns the stable code of the kind
+    pub fn code(&self) -> ErrorCode {
+        use WriteErrorKind::*;
+        match self {
+            Fail => ErrorCode::WriteFailed,
+            StringTooLong { .. } => ErrorCode::StringTooLong,
+            LengthOverflow { .. } => ErrorCode::LengthOverflow,
+            UnmappableChar { .. } => ErrorCode::UnmappableChar,
+            UnsupportedEncoding { .. } => ErrorCode::UnsupportedEncoding,
+            TimestampOutOfRange { .. } => ErrorCode::TimestampOutOfRange,
+            BcdOverflow { .. } => ErrorCode::BcdOverflow,
+            VarintOverflow { .. } => ErrorCode::VarintOverflow,
+            WriteOutOfRange { .. } => ErrorCode::WriteOutOfRange,
+        }
+    }
+}
+
+impl From<std::io::Error> for ErrorKind {
+    fn from(err: std::io::Error) -> Self {
+        ErrorKind::Io(err)
+    }
+}
+
+impl From<TryFromBytesError> for ErrorKind {
+    fn from(err: TryFromBytesError) -> Self {
+        ErrorKind::Decode(err)
+    }
+}
+
+// an Infallible can't be made, so these conversions can never run
+impl From<Infallible> for ErrorKind {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+impl From<Infallible> for Error {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error::new(kind, None)
+    }
+}
+
+// an Error converted to an io::Error converts back to itself
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        if !err.get_ref().is_some_and(|inner| inner.is::<Error>()) {
+            return ErrorKind::Io(err).into();
+        }
+        match err.into_inner().map(|inner| inner.downcast::<Error>()) {
+            Some(Ok(inner)) => *inner,
+            _ => unreachable!("the inner error was checked to be an Error"),
+        }
+    }
+}
+
+/// Converts to an io::Error holding the Error, for use in functions returning `io::Result`
+///
+/// Running out of bytes maps to `UnexpectedEof`, misuse of a reader or writer to
+/// `InvalidInput`, and invalid bytes to `InvalidData`. Errors from the underlying source
+/// keep their kind. The Error can be recovered with `into_inner` and `downcast`.
+///
+/// # Examples
+/// ```
+/// #![feature(generic_const_exprs)]
+///
+/// use std::io;
+///
+/// use bitchomp::{ByteReader, Endianness, Error};
+///
+/// fn version(buf: &[u8]) -> io::Result<u16> {
+///     Ok(ByteReader::new(buf, Endianness::Big).read_value::<u16>()?)
+/// }
+///
+/// let err = version(&[0x01]).unwrap_err();
+/// assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+/// let err = err.into_inner().unwrap().downcast::<Error>().unwrap();
+/// assert_eq!(err.position(), Some(0));
+/// ```
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        let mut kind = &err.kind;
+        while let ErrorKind::InSegment { error, .. } = kind {
+            kind = error;
+        }
+        let io_kind = match kind {
+            ErrorKind::Io(inner) => inner.kind(),
+            _ if err.is_eof() => io::ErrorKind::UnexpectedEof,
+            ErrorKind::SeekOutOfRange { .. }
+            | ErrorKind::SplitOutOfRange { .. }
+            | ErrorKind::StaleMark { .. }
+            | ErrorKind::EmptyPositionStack
+            | ErrorKind::InvalidAlignment { .. }
+            | ErrorKind::UnsupportedEncoding { .. }
+            | ErrorKind::VariableSize
+            | ErrorKind::Write(_) => io::ErrorKind::InvalidInput,
+            ErrorKind::Custom(_) => io::ErrorKind::Other,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(io_kind, err)
+    }
+}
+
+impl From<TryFromBytesError> for Error {
+    fn from(err: TryFromBytesError) -> Self {
+        ErrorKind::Decode(err).into()
+    }
+}
+
+impl From<FromUtf8Error> for Error {
+    fn from(err: FromUtf8Error) -> Self {
+        TryFromBytesError::from(err).into()
+    }
+}
+
+// a value out of range of an odd-width integer is invalid data, like any other bad value
+impl From<TryFromIntError> for Error {
+    fn from(err: TryFromIntError) -> Self {
+        Error::invalid_data(err)
+    }
+}
+
+impl From<WriteErrorKind> for Error {
+    fn from(kind: WriteErrorKind) -> Self {
+        ErrorKind::Write(kind).into()
+    }
+}
+
+/// A Result with bitchomp's Error
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[deprecated(note = "use Error")]
+pub type ByteError = Error;
+
+#[deprecated(note = "use Error")]
+pub type ByteReaderError = Error;
+
+#[deprecated(note = "use ErrorKind")]
+pub type ByteReaderErrorKind = ErrorKind;
@@ -0,0 +1,40 @@
+//! float.rs
+use std::io::BufRead;
+
+use super::{ByteReader, Endianness, Error};
+
+macro_rules! float_slice_impls {
+    ($($t:ty: $read:ident),+) => {$(
+        #[doc = concat!("Reads `n` ", stringify!($t), "s with a single bounds check")]
+        ///
+        /// The bytes are copied in bulk and only swapped if the endianness isn't native,
+        /// so prefer this to `read_n` for large float payloads such as vertex buffers.
+        pub fn $read(&mut self, n: usize) -> Result<Vec<$t>, Error> {
+            const SIZE: usize = std::mem::size_of::<$t>();
+            let len = match n.checked_mul(SIZE) {
+                Some(len) if len <= self.len() => len,
+                len => return Err(self.eof_err::<$t>(len.unwrap_or(usize::MAX), self.len())),
+            };
+            let mut values: Vec<$t> = vec![0.0; n];
+            // SAFETY: values holds exactly len bytes, and any bit pattern is a valid float
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    self.cursor.as_ptr(),
+                    values.as_mut_ptr() as *mut u8,
+                    len,
+                );
+            }
+            if self.endianness != Endianness::native() {
+                for value in values.iter_mut() {
+                    *value = <$t>::from_bits(value.to_bits().swap_bytes());
+                }
+            }
+            self.consume(len);
+            Ok(values)
+        }
+    )+};
+}
+
+impl<'a> ByteReader<'a> {
+    float_slice_impls!(f32: read_f32_slice, f64: read_f64_slice);
+}
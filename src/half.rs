@@ -0,0 +1,153 @@
+//! half.rs
+#![allow(non_camel_case_types)]
+use std::{cmp::Ordering, fmt};
+
+use super::{Endianness, ToBytes, TryFromBytes, TryFromBytesError};
+
+/// An IEEE 754 half precision float, stored in 2 bytes
+#[derive(Default, Clone, Copy)]
+pub struct f16(u16);
+
+impl f16 {
+    pub const ZERO: Self = Self(0x0000);
+    pub const ONE: Self = Self(0x3C00);
+    pub const INFINITY: Self = Self(0x7C00);
+    pub const NEG_INFINITY: Self = Self(0xFC00);
+    pub const NAN: Self = Self(0x7E00);
+    pub const MAX: Self = Self(0x7BFF);
+    pub const MIN: Self = Self(0xFBFF);
+
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    pub const fn is_nan(self) -> bool {
+        self.0 & 0x7C00 == 0x7C00 && self.0 & 0x03FF != 0
+    }
+
+    /// Converts an f32 to the nearest f16, rounding ties to even
+    pub fn from_f32(value: f32) -> Self {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xFF) as i32;
+        let mantissa = bits & 0x7F_FFFF;
+
+        // infinity and NaN, keeping NaNs quiet
+        if exponent == 0xFF {
+            let nan = if mantissa != 0 { 0x0200 } else { 0 };
+            return Self(sign | 0x7C00 | nan | (mantissa >> 13) as u16);
+        }
+
+        let exponent = exponent - 127 + 15;
+        if exponent >= 0x1F {
+            return Self(sign | 0x7C00);
+        }
+
+        // split into the kept bits and the remainder lost in the conversion
+        let (half, remainder, halfway) = if exponent <= 0 {
+            if exponent < -10 {
+                return Self(sign);
+            }
+            let mantissa = mantissa | 0x80_0000;
+            let shift = (14 - exponent) as u32;
+            (
+                mantissa >> shift,
+                mantissa & ((1 << shift) - 1),
+                1 << (shift - 1),
+            )
+        } else {
+            (
+                ((exponent as u32) << 10) | (mantissa >> 13),
+                mantissa & 0x1FFF,
+                0x1000,
+            )
+        };
+        // a carry out of the mantissa correctly bumps the exponent
+        let rounded = if remainder > halfway || (remainder == halfway && half & 1 == 1) {
+            half + 1
+        } else {
+            half
+        };
+        Self(sign | rounded as u16)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        let sign = ((self.0 & 0x8000) as u32) << 16;
+        let exponent = ((self.0 >> 10) & 0x1F) as u32;
+        let mantissa = (self.0 & 0x03FF) as u32;
+        match exponent {
+            0 => {
+                // subnormals are mantissa * 2^-24
+                let value = mantissa as f32 * f32::from_bits(0x3380_0000);
+                f32::from_bits(sign | value.to_bits())
+            }
+            0x1F => f32::from_bits(sign | 0x7F80_0000 | (mantissa << 13)),
+            _ => f32::from_bits(sign | ((exponent + 127 - 15) << 23) | (mantissa << 13)),
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.to_f32() as f64
+    }
+}
+
+impl From<f16> for f32 {
+    fn from(value: f16) -> Self {
+        value.to_f32()
+    }
+}
+
+impl From<f16> for f64 {
+    fn from(value: f16) -> Self {
+        value.to_f64()
+    }
+}
+
+impl PartialEq for f16 {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_f32() == other.to_f32()
+    }
+}
+
+impl PartialOrd for f16 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.to_f32().partial_cmp(&other.to_f32())
+    }
+}
+
+impl fmt::Debug for f16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_f32(), f)
+    }
+}
+
+impl fmt::Display for f16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f32(), f)
+    }
+}
+
+impl TryFromBytes for f16 {
+    type Bytes = Vec<u8>;
+    type Error = TryFromBytesError;
+
+    fn try_from_bytes(
+        bytes: Self::Bytes,
+        endianness: Endianness,
+    ) -> Result<(Self, usize), Self::Error> {
+        let (bits, size) = u16::try_from_bytes(bytes, endianness)?;
+        Ok((Self(bits), size))
+    }
+}
+
+impl ToBytes for f16 {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+        self.0.to_bytes(endianness)
+    }
+}
@@ -0,0 +1,134 @@
+//! ints.rs
+#![allow(non_camel_case_types)]
+use std::fmt;
+
+use super::{Endianness, ToBytes, TryFromBytes, TryFromBytesError};
+
+/// Error returned when converting a value that doesn't fit into an odd-width integer
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TryFromIntError(());
+
+impl fmt::Display for TryFromIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "out of range integral type conversion attempted")
+    }
+}
+
+impl std::error::Error for TryFromIntError {}
+
+/// Reads `bytes` as an unsigned integer in the given endianness
+fn read_uint(bytes: &[u8], endianness: Endianness) -> u64 {
+    let fold = |acc: u64, b: &u8| (acc << 8) | *b as u64;
+    match endianness {
+        Endianness::Big => bytes.iter().fold(0, fold),
+        Endianness::Little => bytes.iter().rev().fold(0, fold),
+    }
+}
+
+/// Writes the low `size` bytes of `value` in the given endianness
+fn write_uint(value: u64, size: usize, endianness: Endianness) -> Vec<u8> {
+    let bytes = &value.to_le_bytes()[..size];
+    match endianness {
+        Endianness::Big => bytes.iter().rev().copied().collect(),
+        Endianness::Little => bytes.to_vec(),
+    }
+}
+
+macro_rules! odd_int {
+    ($(#[$meta:meta])* $name:ident($repr:ty, $wide:ty), $size:literal, $min:expr, $max:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+        pub struct $name($repr);
+
+        impl $name {
+            pub const BITS: u32 = $size * 8;
+            pub const MIN: Self = Self($min);
+            pub const MAX: Self = Self($max);
+
+            /// Returns the value as its smallest containing primitive
+            pub const fn get(self) -> $repr {
+                self.0
+            }
+        }
+
+        impl TryFromBytes for $name {
+            type Bytes = Vec<u8>;
+            type Error = TryFromBytesError;
+
+            const SIZE: usize = $size;
+
+            fn try_from_bytes(
+                bytes: Self::Bytes,
+                endianness: Endianness,
+            ) -> Result<(Self, usize), Self::Error> {
+                if bytes.len() < $size {
+                    return Err(TryFromBytesError::OutOfBounds);
+                }
+                let raw = read_uint(&bytes[..$size], endianness);
+                // shifting up and back down sign extends the signed variants
+                let shift = 64 - Self::BITS;
+                let value = if <$repr>::MIN == 0 {
+                    raw as $repr
+                } else {
+                    (((raw << shift) as i64) >> shift) as $repr
+                };
+                Ok((Self(value), $size))
+            }
+        }
+
+        impl ToBytes for $name {
+            type Bytes = Vec<u8>;
+
+            fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+                write_uint(self.0 as u64, $size, endianness)
+            }
+        }
+
+        impl From<$name> for $repr {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl From<$name> for $wide {
+            fn from(value: $name) -> Self {
+                value.0 as $wide
+            }
+        }
+
+        impl TryFrom<$repr> for $name {
+            type Error = TryFromIntError;
+
+            fn try_from(value: $repr) -> Result<Self, Self::Error> {
+                if ($min..=$max).contains(&value) {
+                    Ok(Self(value))
+                } else {
+                    Err(TryFromIntError(()))
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+odd_int!(
+    /// A 24 bit unsigned integer, stored in 3 bytes
+    u24(u32, u64), 3, 0, 0xFF_FFFF
+);
+odd_int!(
+    /// A 24 bit signed integer, stored in 3 bytes
+    i24(i32, i64), 3, -0x80_0000, 0x7F_FFFF
+);
+odd_int!(
+    /// A 48 bit unsigned integer, stored in 6 bytes
+    u48(u64, u128), 6, 0, 0xFFFF_FFFF_FFFF
+);
+odd_int!(
+    /// A 48 bit signed integer, stored in 6 bytes
+    i48(i64, i128), 6, -0x8000_0000_0000, 0x7FFF_FFFF_FFFF
+);
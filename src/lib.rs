@@ -7,15 +7,52 @@
     debug_closure_helpers
 )]
 
+extern crate self as bitchomp;
+
+#[cfg(feature = "tokio")]
+mod asyncreader;
+mod bcd;
+mod bitreader;
+mod bitwriter;
 mod bytereader;
 mod bytewriter;
+mod chained;
+mod error;
+#[cfg(feature = "encodings")]
+mod encoding;
+mod float;
+mod half;
+mod ints;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod net;
+mod owned;
+mod streaming;
+mod time;
+mod uuid;
 mod transmutable;
+mod varint;
 mod chomp;
 
 #[cfg(test)]
 mod test;
 
+#[cfg(feature = "tokio")]
+pub use asyncreader::*;
+pub use bitchomp_derive::*;
+pub use bitreader::*;
+pub use bitwriter::*;
 pub use bytereader::*;
 pub use bytewriter::*;
+pub use chained::*;
+pub use error::*;
+pub use half::*;
+pub use ints::*;
+#[cfg(feature = "mmap")]
+pub use mmap::*;
+pub use owned::*;
+pub use streaming::*;
 pub use transmutable::*;
+pub use uuid::*;
+pub use varint::*;
 pub use chomp::*;
\ No newline at end of file
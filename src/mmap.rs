@@ -0,0 +1,61 @@
+//! mmap.rs
+use std::{fs::File, path::Path};
+
+use memmap2::Mmap;
+
+use super::{ByteReader, Endianness, Error};
+
+/// A memory mapped file, returned by `ByteReader::from_mmap`
+///
+/// Pages of the file are only read from disk as they are used, so large files don't
+/// have to be read into memory up front.
+pub struct MappedBytes {
+    map: Mmap,
+    endianness: Endianness,
+}
+
+impl MappedBytes {
+    /// Returns a ByteReader over the whole file
+    pub fn reader(&self) -> ByteReader<'_> {
+        ByteReader::new(&self.map, self.endianness)
+    }
+}
+
+impl<'a> ByteReader<'a> {
+    /// Memory maps the file at `path` for reading
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other process, while the
+    /// MappedBytes is alive. The readers assume the bytes don't change underneath them, and
+    /// accessing pages past the end of a truncated file is undefined behaviour. See
+    /// `memmap2::Mmap::map`.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     // SAFETY: nothing writes to the test files
+    ///     let file = unsafe { ByteReader::from_mmap("test/binary.file", Endianness::Little)? };
+    ///     let mut reader = file.reader();
+    ///
+    ///     let value = reader.read::<u32>()?.inner();
+    ///
+    ///     // ... do stuff
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub unsafe fn from_mmap(
+        path: impl AsRef<Path>,
+        endianness: Endianness,
+    ) -> Result<MappedBytes, Error> {
+        let file = File::open(path)?;
+        // SAFETY: the map is read only, and the caller guarantees the file isn't modified
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(MappedBytes { map, endianness })
+    }
+}
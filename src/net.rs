@@ -0,0 +1,114 @@
+//! net.rs
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
+use super::{Endianness, ToBytes, TryFromBytes, TryFromBytesError};
+
+/// IPv4 addresses are stored as their 4 octets in order, regardless of endianness
+impl TryFromBytes for Ipv4Addr {
+    type Bytes = Vec<u8>;
+    type Error = TryFromBytesError;
+
+    const SIZE: usize = 4;
+
+    fn try_from_bytes(bytes: Self::Bytes, _: Endianness) -> Result<(Self, usize), Self::Error> {
+        let octets: [u8; 4] = bytes
+            .get(..4)
+            .ok_or(TryFromBytesError::OutOfBounds)?
+            .try_into()
+            .or(Err(TryFromBytesError::ArrayFromSlice))?;
+        Ok((Ipv4Addr::from(octets), 4))
+    }
+}
+
+impl ToBytes for Ipv4Addr {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, _: Endianness) -> Self::Bytes {
+        self.octets().to_vec()
+    }
+}
+
+/// IPv6 addresses are stored as their 16 octets in order, regardless of endianness
+impl TryFromBytes for Ipv6Addr {
+    type Bytes = Vec<u8>;
+    type Error = TryFromBytesError;
+
+    const SIZE: usize = 16;
+
+    fn try_from_bytes(bytes: Self::Bytes, _: Endianness) -> Result<(Self, usize), Self::Error> {
+        let octets: [u8; 16] = bytes
+            .get(..16)
+            .ok_or(TryFromBytesError::OutOfBounds)?
+            .try_into()
+            .or(Err(TryFromBytesError::ArrayFromSlice))?;
+        Ok((Ipv6Addr::from(octets), 16))
+    }
+}
+
+impl ToBytes for Ipv6Addr {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, _: Endianness) -> Self::Bytes {
+        self.octets().to_vec()
+    }
+}
+
+/// Socket addresses are stored as the address followed by a u16 port in the given endianness
+impl TryFromBytes for SocketAddrV4 {
+    type Bytes = Vec<u8>;
+    type Error = TryFromBytesError;
+
+    const SIZE: usize = 6;
+
+    fn try_from_bytes(
+        bytes: Self::Bytes,
+        endianness: Endianness,
+    ) -> Result<(Self, usize), Self::Error> {
+        if bytes.len() < Self::SIZE {
+            return Err(TryFromBytesError::OutOfBounds);
+        }
+        let (ip, size) = Ipv4Addr::try_from_bytes(bytes.clone(), endianness)?;
+        let (port, _) = u16::try_from_bytes(bytes[size..].to_vec(), endianness)?;
+        Ok((SocketAddrV4::new(ip, port), 6))
+    }
+}
+
+impl ToBytes for SocketAddrV4 {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+        let mut bytes = self.ip().to_bytes(endianness);
+        bytes.append(&mut self.port().to_bytes(endianness));
+        bytes
+    }
+}
+
+/// The flow info and scope id aren't stored, and are read as 0
+impl TryFromBytes for SocketAddrV6 {
+    type Bytes = Vec<u8>;
+    type Error = TryFromBytesError;
+
+    const SIZE: usize = 18;
+
+    fn try_from_bytes(
+        bytes: Self::Bytes,
+        endianness: Endianness,
+    ) -> Result<(Self, usize), Self::Error> {
+        if bytes.len() < Self::SIZE {
+            return Err(TryFromBytesError::OutOfBounds);
+        }
+        let (ip, size) = Ipv6Addr::try_from_bytes(bytes.clone(), endianness)?;
+        let (port, _) = u16::try_from_bytes(bytes[size..].to_vec(), endianness)?;
+        Ok((SocketAddrV6::new(ip, port, 0, 0), 18))
+    }
+}
+
+impl ToBytes for SocketAddrV6 {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+        let mut bytes = self.ip().to_bytes(endianness);
+        bytes.append(&mut self.port().to_bytes(endianness));
+        bytes
+    }
+}
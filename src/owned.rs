@@ -0,0 +1,142 @@
+//! owned.rs
+use std::{io::BufRead, sync::Arc};
+
+use super::{ByteReader, ByteReaderResource, Endianness, Error};
+
+/// A ByteReader that owns its buffer, so it can be returned from functions and sent
+/// between threads
+///
+/// The buffer is shared, so clones are cheap and read independently. Values are
+/// returned decoded rather than as Chomps, and `with_reader` gives access to the rest
+/// of the ByteReader API.
+#[derive(Clone)]
+pub struct ByteReaderOwned {
+    buf: Arc<[u8]>,
+    cursor: usize,
+    endianness: Endianness,
+    /// The position of `buf` in the buffer it was copied from
+    origin: usize,
+}
+
+impl ByteReaderOwned {
+    /// Returns a ByteReaderOwned reading from buf
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReaderOwned, Endianness, Error};
+    ///
+    /// fn open(path: &str) -> Result<ByteReaderOwned, Error> {
+    ///     Ok(ByteReaderOwned::new(std::fs::read(path)?, Endianness::Little))
+    /// }
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let mut reader = open("test/binary.file")?;
+    ///     let value = reader.read::<u32>()?;
+    ///
+    ///     // ... do stuff
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn new(buf: impl Into<Arc<[u8]>>, endianness: Endianness) -> Self {
+        ByteReaderOwned {
+            buf: buf.into(),
+            cursor: 0,
+            endianness,
+            origin: 0,
+        }
+    }
+
+    /// Runs `f` with a ByteReader at the cursor, keeping any bytes it consumes
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReaderOwned, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let mut reader = ByteReaderOwned::new(*b"\x03abc", Endianness::Little);
+    ///
+    ///     let name = reader.with_reader(|r| {
+    ///         let len = r.read::<u8>()?.inner() as usize;
+    ///         r.read_string_fixed(len)
+    ///     })?;
+    ///     assert_eq!(name, "abc");
+    ///     assert!(reader.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_reader<T>(&mut self, f: impl FnOnce(&mut ByteReader) -> T) -> T {
+        let mut reader = ByteReader::with_origin(&self.buf, self.endianness, self.origin);
+        reader.consume(self.cursor);
+        let value = f(&mut reader);
+        self.cursor = reader.absolute_cursor() - self.origin;
+        value
+    }
+
+    /// Returns the length of the remaining buffer
+    pub fn len(&self) -> usize {
+        self.buf.len() - self.cursor
+    }
+
+    /// Returns true if there are no bytes left to read
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the cursor position
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Seeks to a position in the buffer, like `ByteReader::seek`
+    pub fn seek(&mut self, pos: usize) -> Result<(), Error> {
+        self.with_reader(|r| r.seek(pos))
+    }
+
+    /// Reads a type T from the buffer
+    pub fn read<T: for<'a> ByteReaderResource<'a>>(&mut self) -> Result<T, Error> {
+        self.with_reader(|r| r.read::<T>().map(|c| c.inner()))
+    }
+
+    /// Reads a type T from the buffer n times
+    pub fn read_n<T: for<'a> ByteReaderResource<'a>>(&mut self, n: usize) -> Result<Vec<T>, Error> {
+        self.with_reader(|r| Ok(r.read_n::<T>(n)?.iter().map(|c| c.inner()).collect()))
+    }
+
+    /// Reads a type T from the buffer without consuming
+    pub fn peek<T: for<'a> ByteReaderResource<'a>>(&mut self) -> Result<T, Error> {
+        self.with_reader(|r| r.peek::<T>().map(|c| c.inner()))
+    }
+
+    /// Reads a type T from the buffer n times without consuming
+    pub fn peek_n<T: for<'a> ByteReaderResource<'a>>(&mut self, n: usize) -> Result<Vec<T>, Error> {
+        self.with_reader(|r| Ok(r.peek_n::<T>(n)?.iter().map(|c| c.inner()).collect()))
+    }
+
+    /// Reads a NUL-terminated string
+    pub fn read_string(&mut self) -> Result<String, Error> {
+        self.with_reader(|r| r.read_string())
+    }
+
+    /// Reads a NUL-terminated string, replacing invalid UTF-8 with U+FFFD
+    pub fn read_string_lossy(&mut self) -> Result<String, Error> {
+        self.with_reader(|r| r.read_string_lossy())
+    }
+}
+
+impl<'a> ByteReader<'a> {
+    /// Returns a ByteReaderOwned with a copy of the buffer, at the same position
+    pub fn to_owned(&self) -> ByteReaderOwned {
+        ByteReaderOwned {
+            buf: self.buf.into(),
+            cursor: self.cursor(),
+            endianness: self.endianness,
+            origin: self.origin,
+        }
+    }
+}
@@ -0,0 +1,336 @@
+//! streaming.rs
+use std::io;
+
+use super::{ByteReader, ByteReaderResource, Endianness, Error, ErrorKind};
+
+/// The most bytes buffered for a length prefixed vector unless set otherwise
+const DEFAULT_MAX_BUFFER: usize = 64 << 20;
+
+/// The buffer behind StreamingByteReader and AsyncByteReader, which decodes values from
+/// the bytes read so far and leaves reading from the source to them
+pub(crate) struct StreamBuffer {
+    /// Bytes read from the source, of which those from `start` are unread
+    pub(crate) buf: Vec<u8>,
+    start: usize,
+    /// The position in the stream of `buf[start]`
+    position: usize,
+    /// The number of bytes to read from the source at a time
+    pub(crate) chunk: usize,
+    /// The most bytes to buffer for a length prefixed vector
+    pub(crate) max_buffer: usize,
+    pub(crate) eof: bool,
+    endianness: Endianness,
+}
+
+impl StreamBuffer {
+    pub(crate) fn new(endianness: Endianness) -> Self {
+        StreamBuffer {
+            buf: Vec::new(),
+            start: 0,
+            position: 0,
+            chunk: 8192,
+            max_buffer: DEFAULT_MAX_BUFFER,
+            eof: false,
+            endianness,
+        }
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    pub(crate) fn err(&self, kind: ErrorKind) -> Error {
+        Error::new(kind, Some(self.position))
+    }
+
+    /// Returns an `UnexpectedEof` error for a T at stream position `start` needing
+    /// `requested` bytes, when the stream ended `available` bytes later
+    pub(crate) fn eof_err<T: ?Sized>(start: usize, requested: usize, available: usize) -> Error {
+        let kind = ErrorKind::UnexpectedEof {
+            requested,
+            available,
+            type_name: std::any::type_name::<T>(),
+        };
+        Error::new(kind, Some(start))
+    }
+
+    /// Returns the buffered bytes that haven't been read yet
+    pub(crate) fn window(&self) -> &[u8] {
+        &self.buf[self.start..]
+    }
+
+    pub(crate) fn consume(&mut self, amt: usize) {
+        self.start += amt;
+        self.position += amt;
+    }
+
+    /// Moves the position past bytes that were skipped in the source without buffering
+    pub(crate) fn skip_unbuffered(&mut self, amt: usize) {
+        self.position += amt;
+    }
+
+    /// Drops the bytes already read, before reading more from the source
+    pub(crate) fn compact(&mut self) {
+        self.buf.drain(..self.start);
+        self.start = 0;
+    }
+
+    /// Runs `f` on a ByteReader over the buffered bytes, consuming what it reads
+    ///
+    /// Returns None if more of the stream is needed, either because fewer than `min_len`
+    /// bytes are buffered or because `f` ran out of bytes.
+    pub(crate) fn try_decode<V>(
+        &mut self,
+        min_len: usize,
+        f: impl FnOnce(&mut ByteReader) -> Result<V, Error>,
+    ) -> Result<Option<V>, Error> {
+        if self.window().len() < min_len && !self.eof {
+            return Ok(None);
+        }
+        let mut reader = ByteReader::with_origin(self.window(), self.endianness, self.position);
+        match f(&mut reader) {
+            Ok(value) => {
+                let read = reader.cursor();
+                self.consume(read);
+                Ok(Some(value))
+            }
+            Err(e) if !self.eof && e.kind.is_eof() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decodes n values of type T, or returns None if more of the stream is needed
+    pub(crate) fn try_read_n<T: for<'a> ByteReaderResource<'a>>(
+        &mut self,
+        n: usize,
+    ) -> Result<Option<Vec<T>>, Error> {
+        let min_len = if T::VARIABLE_SIZE {
+            0
+        } else {
+            T::SIZE.saturating_mul(n)
+        };
+        self.try_decode(min_len, |r| {
+            Ok(r.read_n::<T>(n)?.iter().map(|c| c.inner()).collect())
+        })
+    }
+
+    /// Decodes a vector of T prefixed by its u32 length, or returns None if more of the
+    /// stream is needed
+    ///
+    /// Errors with `DeclaredSizeTooLarge` rather than buffer more than `max_buffer` bytes,
+    /// so a corrupt or hostile length can't exhaust memory. Fixed size values wait for the
+    /// whole vector before decoding, and variable size ones for `want` bytes, which doubles
+    /// each time, so the vector isn't decoded again after every chunk.
+    pub(crate) fn try_read_sized_vector<T: for<'a> ByteReaderResource<'a>>(
+        &mut self,
+        want: &mut usize,
+    ) -> Result<Option<Vec<T>>, Error> {
+        let mut reader = ByteReader::with_origin(self.window(), self.endianness, self.position);
+        let declared = match reader.read::<u32>() {
+            Ok(declared) => declared.inner() as usize,
+            Err(_) if !self.eof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let min_len = if T::VARIABLE_SIZE {
+            *want
+        } else {
+            declared
+                .checked_mul(T::SIZE)
+                .and_then(|len| len.checked_add(reader.cursor()))
+                .unwrap_or(usize::MAX)
+        };
+        let too_large = self.err(ErrorKind::DeclaredSizeTooLarge {
+            declared,
+            remaining: self.max_buffer,
+        });
+        if min_len > self.max_buffer {
+            return Err(too_large);
+        }
+        let values = self.try_decode(min_len, |r| {
+            Ok(r.read_sized_vector::<T>()?
+                .iter()
+                .map(|c| c.inner())
+                .collect())
+        })?;
+        if values.is_none() {
+            if self.window().len() >= self.max_buffer {
+                return Err(too_large);
+            }
+            *want = self
+                .window()
+                .len()
+                .saturating_mul(2)
+                .clamp(1, self.max_buffer);
+        }
+        Ok(values)
+    }
+
+    /// Decodes a NUL-terminated string, or returns None if more of the stream is needed
+    ///
+    /// `scanned` is how much of the buffer is known not to hold a NUL byte, so repeated
+    /// calls don't search the same bytes.
+    pub(crate) fn try_read_string(&mut self, scanned: &mut usize) -> Result<Option<String>, Error> {
+        let Some(i) = self.window()[*scanned..].iter().position(|&b| b == 0x00) else {
+            *scanned = self.window().len();
+            if self.eof {
+                return Err(self.err(ErrorKind::UnterminatedString { scanned: *scanned }));
+            }
+            return Ok(None);
+        };
+        let len = *scanned + i;
+        let value = String::from_utf8(self.window()[..len].to_vec()).map_err(|error| {
+            let offset = self.position + error.utf8_error().valid_up_to();
+            self.err(ErrorKind::InvalidUtf8 { offset, error })
+        })?;
+        self.consume(len + 1);
+        Ok(Some(value))
+    }
+}
+
+/// A tool for reading bytes from an io::Read source too large to hold in memory
+///
+/// Bytes are read from the source into a buffer as they are needed, so only the value
+/// being decoded has to fit in memory. Positions in errors are offsets in the stream.
+/// Reading only goes forwards: there is no seeking, and skipping reads and discards.
+pub struct StreamingByteReader<R> {
+    source: R,
+    buf: StreamBuffer,
+}
+
+impl<R: io::Read> StreamingByteReader<R> {
+    /// Returns a StreamingByteReader reading from `source`
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use std::fs::File;
+    ///
+    /// use bitchomp::{Endianness, Error, StreamingByteReader};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let file = File::open("test/binary.file")?;
+    ///     let mut reader = StreamingByteReader::new(file, Endianness::Little);
+    ///
+    ///     let value = reader.read::<u32>()?;
+    ///
+    ///     // ... do stuff
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn new(source: R, endianness: Endianness) -> Self {
+        StreamingByteReader {
+            source,
+            buf: StreamBuffer::new(endianness),
+        }
+    }
+
+    /// Sets the number of bytes read from the source at a time, which is 8192 by default
+    pub fn with_chunk_size(mut self, chunk: usize) -> Self {
+        self.buf.chunk = chunk.max(1);
+        self
+    }
+
+    /// Sets the most bytes `read_sized_vector` will buffer for one vector, 64 MiB by
+    /// default
+    pub fn with_max_buffer(mut self, max_buffer: usize) -> Self {
+        self.buf.max_buffer = max_buffer;
+        self
+    }
+
+    /// Returns the position in the stream
+    pub fn position(&self) -> usize {
+        self.buf.position()
+    }
+
+    /// Reads another chunk from the source, setting `eof` at the end of the stream
+    fn fill_more(&mut self) -> Result<(), Error> {
+        self.buf.compact();
+        let len = self.buf.buf.len();
+        self.buf.buf.resize(len + self.buf.chunk, 0);
+        let read = loop {
+            match self.source.read(&mut self.buf.buf[len..]) {
+                Ok(read) => break read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.buf.buf.truncate(len);
+                    return Err(self.buf.err(ErrorKind::Io(e)));
+                }
+            }
+        };
+        self.buf.buf.truncate(len + read);
+        self.buf.eof = read == 0;
+        Ok(())
+    }
+
+    /// Reads a type T from the stream
+    ///
+    /// Errors with `UnexpectedEof` if the stream ends partway through the value.
+    pub fn read<T: for<'a> ByteReaderResource<'a>>(&mut self) -> Result<T, Error> {
+        Ok(self.read_n(1)?.remove(0))
+    }
+
+    /// Reads a type T from the stream n times
+    ///
+    /// Errors with `UnexpectedEof` if the stream ends partway through the values, without
+    /// consuming any of them.
+    pub fn read_n<T: for<'a> ByteReaderResource<'a>>(&mut self, n: usize) -> Result<Vec<T>, Error> {
+        loop {
+            if let Some(values) = self.buf.try_read_n(n)? {
+                return Ok(values);
+            }
+            self.fill_more()?;
+        }
+    }
+
+    /// Reads a vector of T prefixed by its u32 length, like `ByteReader::read_sized_vector`
+    ///
+    /// Errors with `DeclaredSizeTooLarge` if the vector would take more than the
+    /// `with_max_buffer` limit, without reading it from the source.
+    pub fn read_sized_vector<T: for<'a> ByteReaderResource<'a>>(
+        &mut self,
+    ) -> Result<Vec<T>, Error> {
+        let mut want = 0;
+        loop {
+            if let Some(values) = self.buf.try_read_sized_vector(&mut want)? {
+                return Ok(values);
+            }
+            self.fill_more()?;
+        }
+    }
+
+    /// Reads a NUL-terminated UTF-8 string from the stream
+    ///
+    /// Errors with `UnterminatedString` if the stream ends before a NUL byte.
+    pub fn read_string(&mut self) -> Result<String, Error> {
+        let mut scanned = 0;
+        loop {
+            if let Some(value) = self.buf.try_read_string(&mut scanned)? {
+                return Ok(value);
+            }
+            self.fill_more()?;
+        }
+    }
+
+    /// Skips `n` bytes of the stream
+    ///
+    /// Errors with `UnexpectedEof` if the stream ends first, having skipped to its end.
+    pub fn skip_bytes(&mut self, n: usize) -> Result<(), Error> {
+        let start = self.buf.position();
+        let buffered = n.min(self.buf.window().len());
+        self.buf.consume(buffered);
+        let rest = (n - buffered) as u64;
+        let skipped = io::copy(&mut io::Read::take(&mut self.source, rest), &mut io::sink())
+            .map_err(|e| self.buf.err(ErrorKind::Io(e)))?;
+        // the bytes never went through the buffer, so only the position moves
+        self.buf.compact();
+        self.buf.skip_unbuffered(skipped as usize);
+        if skipped < rest {
+            self.buf.eof = true;
+            let available = self.buf.position() - start;
+            return Err(StreamBuffer::eof_err::<[u8]>(start, n, available));
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,20 @@
-use std::time::{Duration, Instant};
+use std::{
+    num::{NonZeroI16, NonZeroU32, NonZeroU64, NonZeroU8},
+    time::{Duration, Instant},
+};
 
-use crate::ChompFlatten;
+use crate::{
+    f16, i24, u24, u48, BitOrder, BitReader, BitWriter, ByteReaderOwned, ChompEnum, ChompFlatten,
+    StreamingByteReader,
+};
 
-use super::{ByteError, ByteReader, ByteWriter, Endianness};
+use super::{
+    ByteReader, ByteWriter, Endianness, Error, ErrorCode, ErrorKind, ParseEndiannessError, ToBytes,
+    TryFromBytes, TryFromBytesError, WriteErrorKind,
+};
 
 #[test]
-fn test_bytewriter() -> Result<(), ByteError> {
+fn test_bytewriter() -> Result<(), Error> {
     let mut writer = ByteWriter::new(Endianness::default());
     writer.append::<u16>(10);
     writer.append::<String>(String::from("testing"));
@@ -13,32 +22,52 @@ fn test_bytewriter() -> Result<(), ByteError> {
 
     let b = writer.buf();
     let mut reader = ByteReader::new(b.as_slice(), Endianness::default());
-    assert_eq!(reader.read::<u16>()?.inner(), 10);
+    assert_eq!(reader.read_value::<u16>()?, 10);
     assert_eq!(reader.read_string()?, String::from("testing"));
-    assert_eq!(reader.read::<i32>()?.inner(), -14);
+    assert_eq!(reader.read_value::<i32>()?, -14);
     Ok(())
 }
 
 #[test]
-fn test_bytereader() -> Result<(), ByteError> {
+fn test_bytereader() -> Result<(), Error> {
     let data = std::fs::read("test/texture.text")?;
     let mut reader = ByteReader::new(&data, Endianness::default());
-    assert_eq!(reader.read::<u16>()?.inner(), 1);
-    assert_eq!(reader.read::<u16>()?.inner(), 0);
-    assert_eq!(reader.read::<u32>()?.inner(), 0x4000);
-    assert_eq!(reader.read::<u32>()?.inner(), 0x2B0C);
-    assert_eq!(reader.read::<u32>()?.inner(), 0);
-    assert_eq!(reader.read::<u16>()?.inner(), 0x0080);
-    assert_eq!(reader.read::<u16>()?.inner(), 0x0080);
-    assert_eq!(reader.read::<u16>()?.inner(), 0x0049);
+    assert_eq!(reader.read::<u16>()?, 1);
+    assert_eq!(reader.read::<u16>()?, 0);
+    assert_eq!(reader.read::<u32>()?, 0x4000);
+    assert_eq!(reader.read::<u32>()?, 0x2B0C);
+    assert_eq!(reader.read::<u32>()?, 0);
+    assert_eq!(reader.read::<u16>()?, 0x0080);
+    assert_eq!(reader.read::<u16>()?, 0x0080);
+    assert_eq!(reader.read::<u16>()?, 0x0049);
     reader.seek(0x1c)?;
     assert_eq!(
-        reader.read_n::<u32>(14)?.flatten(),
+        reader.read_n::<u32>(14)?,
         vec![8192, 10240, 10752, 10880, 10912, 10920, 10928, 10936, 0, 0, 0, 0, 0, 0,]
     );
     Ok(())
 }
 
+#[test]
+fn test_bytereader_values() -> Result<(), Error> {
+    let data = std::fs::read("test/texture.text")?;
+    let mut reader = ByteReader::new(&data, Endianness::default());
+    assert_eq!(reader.read_value::<u16>()?, 1);
+    assert_eq!(reader.read_value::<u16>()?, 0);
+    assert_eq!(reader.peek_value::<u32>()?, 0x4000);
+    assert_eq!(reader.read_value::<u32>()?, 0x4000);
+    assert_eq!(reader.read_value::<u32>()?, 0x2B0C);
+    assert_eq!(reader.read_value::<u32>()?, 0);
+    assert_eq!(reader.read_n_values::<u16>(3)?, [0x0080, 0x0080, 0x0049]);
+    reader.seek(0x1c)?;
+    assert_eq!(
+        reader.read_n_values::<u32>(14)?,
+        vec![8192, 10240, 10752, 10880, 10912, 10920, 10928, 10936, 0, 0, 0, 0, 0, 0,]
+    );
+    assert!(reader.read_n_values::<u32>(data.len()).is_err());
+    Ok(())
+}
+
 /// Utility class for timing functions
 struct Timer {
     instant: Instant,
@@ -57,12 +86,12 @@ impl Timer {
     fn time(&mut self) -> Duration {
         let ret = self.instant.elapsed();
         self.restart();
-        return ret;
+        ret
     }
 }
 
 #[test]
-fn benchmark_bytereader() -> Result<(), ByteError> {
+fn benchmark_bytereader() -> Result<(), Error> {
     let mut timer = Timer::new();
     let data = std::fs::read("test/texture.text")?;
     println!("std::fs::read(\"texture.text\"): {:#?}", timer.time());
@@ -78,11 +107,11 @@ fn benchmark_bytereader() -> Result<(), ByteError> {
         timer.time(),
         len
     );
-    reader.rebase(0);
+    reader.rebase(0)?;
     bytes = reader.read_n::<u8>(len)?;
     println!("reader.read_n::<u8>({})?: {:#?}", len, timer.time());
     assert_eq!(bytes.len(), len);
-    reader.rebase(0);
+    reader.rebase(0)?;
 
     timer.restart();
     // assign to avoid it getting thrown out
@@ -94,17 +123,17 @@ fn benchmark_bytereader() -> Result<(), ByteError> {
 }
 
 #[test]
-fn bytereader_raw_reading() -> Result<(), ByteError> {
+fn bytereader_raw_reading() -> Result<(), Error> {
     let data = std::fs::read("test/texture.text")?;
     let mut reader = ByteReader::new(&data, Endianness::default());
     reader.read_n::<u16>(data.len() / 2)?;
-    reader.rebase(0);
+    reader.rebase(0)?;
     assert!(reader.read_n::<u16>(data.len()).is_err());
     Ok(())
 }
 
 #[test]
-fn test_bytereader_read_sized_vector() -> Result<(), ByteError> {
+fn test_bytereader_read_sized_vector() -> Result<(), Error> {
     let data = vec![
         1, 1, 1, 12, 0, 0, 0, 0, 16, 1, 0, 0, 0, 85, 85, 21, 0, 1, 16, 1, 0, 0, 0, 85, 85, 21, 0,
         2, 16, 1, 0, 0, 0, 85, 85, 21, 0, 3, 16, 1, 0, 0, 0, 85, 85, 21, 0, 4, 16, 1, 0, 0, 0, 85,
@@ -116,12 +145,12 @@ fn test_bytereader_read_sized_vector() -> Result<(), ByteError> {
         reader
             .peek_n::<u8>(3)?
             .iter()
-            .map(|v| v.0)
-            .collect::<Vec<*const u8>>()
+            .map(|v| v.bytes())
+            .collect::<Vec<&[u8]>>()
     );
-    assert_eq!(reader.read_n::<u8>(3)?.flatten(), vec![1, 1, 1]);
+    assert_eq!(reader.read_n::<u8>(3)?.into_flattened(), vec![1, 1, 1]);
 
-    println!("{:x?}", reader.peek_n::<u32>(1)?[0].0);
+    println!("{:x?}", reader.peek_n::<u32>(1)?[0].bytes());
     assert_eq!(reader.peek::<u32>()?.inner(), 12);
     let vec: Vec<u32> = reader.read_sized_vector::<u32>()?.flatten();
     assert_eq!(
@@ -133,3 +162,4155 @@ fn test_bytereader_read_sized_vector() -> Result<(), ByteError> {
     );
     Ok(())
 }
+
+#[test]
+fn test_float_roundtrip() -> Result<(), Error> {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        writer.append::<f32>(1.5);
+        writer.append::<f64>(-0.25);
+        writer.write_vec::<f32>(vec![0.0, -1.0, f32::MAX]);
+
+        let b = writer.buf();
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(reader.peek::<f32>()?.inner(), 1.5);
+        assert_eq!(reader.read::<f32>()?.inner(), 1.5);
+        assert_eq!(reader.read::<f64>()?.inner(), -0.25);
+        assert_eq!(
+            reader.read_n::<f32>(3)?.flatten(),
+            vec![0.0, -1.0, f32::MAX]
+        );
+        assert!(reader.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_float_endianness() -> Result<(), Error> {
+    let data = [0x3F, 0x80, 0x00, 0x00];
+    assert_eq!(
+        ByteReader::new(&data, Endianness::Big)
+            .read::<f32>()?
+            .inner(),
+        1.0
+    );
+    assert_eq!(
+        ByteReader::new(&data, Endianness::Little)
+            .read::<f32>()?
+            .inner(),
+        f32::from_bits(0x0000_803F)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bool_roundtrip() -> Result<(), Error> {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        writer.append::<bool>(true);
+        writer.append::<bool>(false);
+        assert_eq!(writer.buf(), vec![0x01, 0x00]);
+
+        let b = writer.buf();
+        let mut reader = ByteReader::new(&b, endianness);
+        assert!(reader.read::<bool>()?.inner());
+        assert!(!reader.read::<bool>()?.inner());
+        assert!(reader.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_strict_bool() -> Result<(), Error> {
+    let data = [0x00, 0x01, 0xCC];
+    let mut reader = ByteReader::new(&data, Endianness::default());
+    assert!(!reader.read_strict_bool()?);
+    assert!(reader.read_strict_bool()?);
+    assert!(reader.read_strict_bool().is_err());
+    assert_eq!(reader.cursor(), 2);
+    assert!(reader.read::<bool>()?.inner());
+    assert!(reader.read_strict_bool().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_char_roundtrip() -> Result<(), Error> {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        writer.append::<char>('a');
+        writer.append::<char>('🦀');
+        writer.append::<u32>(0x10FFFF);
+
+        let b = writer.buf();
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(reader.read::<char>()?.inner(), 'a');
+        assert_eq!(reader.read::<char>()?.inner(), '🦀');
+        assert_eq!(reader.read::<char>()?.inner(), '\u{10FFFF}');
+    }
+    Ok(())
+}
+
+#[test]
+fn test_invalid_char() -> Result<(), Error> {
+    let mut writer = ByteWriter::new(Endianness::default());
+    writer.append::<char>('a');
+    writer.append::<u32>(0xD800);
+    writer.append::<u32>(0x110000);
+
+    let b = writer.buf();
+    let mut reader = ByteReader::new(&b, Endianness::default());
+    assert!(reader.read_n::<char>(2).is_err());
+    assert_eq!(reader.cursor(), 0);
+    assert_eq!(reader.read::<char>()?.inner(), 'a');
+    assert!(reader.read::<char>().is_err());
+    reader.seek(8)?;
+    assert!(reader.read::<char>().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_odd_width_ints() -> Result<(), Error> {
+    let unsigned = [0, 1, 0x7F_FFFF, 0x80_0000, 0xFF_FFFF].map(|v| u24::try_from(v).unwrap());
+    let signed = [0, -1, 0x7F_FFFF, -0x80_0000].map(|v| i24::try_from(v).unwrap());
+    let wide = u48::try_from(0xFFFF_FFFF_FFFF).unwrap();
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        assert_eq!(writer.write_vec(unsigned.to_vec()), 15);
+        assert_eq!(writer.write_vec(signed.to_vec()), 12);
+        assert_eq!(writer.append(wide), 6);
+
+        let b = writer.buf();
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(reader.read_n::<u24>(5)?.flatten(), unsigned);
+        assert_eq!(reader.cursor(), 15);
+        assert_eq!(reader.read_n::<i24>(4)?.flatten(), signed);
+        assert_eq!(reader.read::<u48>()?.inner(), wide);
+        assert!(reader.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_odd_width_int_layout() -> Result<(), Error> {
+    let data = [0x01, 0x02, 0x80];
+    let le = ByteReader::new(&data, Endianness::Little)
+        .read::<u24>()?
+        .inner();
+    let be = ByteReader::new(&data, Endianness::Big)
+        .read::<u24>()?
+        .inner();
+    assert_eq!(u32::from(le), 0x80_0201);
+    assert_eq!(u32::from(be), 0x01_0280);
+    // 0x800201 has the sign bit set
+    let signed = ByteReader::new(&data, Endianness::Little)
+        .read::<i24>()?
+        .inner();
+    assert_eq!(i32::from(signed), 0x80_0201 - 0x100_0000);
+    assert_eq!(i64::from(i24::MIN), -0x80_0000);
+    assert!(u24::try_from(0x100_0000).is_err());
+    assert!(i24::try_from(0x80_0000).is_err());
+
+    // the conversion error works with ? in both boxed and bitchomp results
+    fn boxed(value: u32) -> Result<u24, Box<dyn std::error::Error>> {
+        Ok(u24::try_from(value)?)
+    }
+    fn unified(value: u32) -> crate::Result<u24> {
+        Ok(u24::try_from(value)?)
+    }
+    assert_eq!(
+        boxed(0x100_0000).unwrap_err().to_string(),
+        unified(0x100_0000).unwrap_err().to_string()
+    );
+    assert_eq!(
+        unified(0x100_0000).unwrap_err().code(),
+        ErrorCode::InvalidData
+    );
+    assert_eq!(unified(0xFF_FFFF)?, u24::MAX);
+    Ok(())
+}
+
+#[test]
+fn test_array_roundtrip() -> Result<(), Error> {
+    let keys = [[0xAAu8; 16], [0x55u8; 16]];
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        writer.append::<[u32; 4]>([1, 2, 3, 0xDEADBEEF]);
+        writer.write_vec(keys.to_vec());
+        writer.append::<[u24; 2]>([u24::MAX, u24::MIN]);
+
+        let b = writer.buf();
+        assert_eq!(b.len(), 16 + 32 + 6);
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(reader.read::<[u32; 4]>()?.inner(), [1, 2, 3, 0xDEADBEEF]);
+        assert_eq!(reader.read_n::<[u8; 16]>(2)?.flatten(), keys);
+        assert_eq!(reader.read::<[u24; 2]>()?.inner(), [u24::MAX, u24::MIN]);
+        assert!(reader.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_array_element_error() {
+    let mut writer = ByteWriter::new(Endianness::default());
+    writer.append::<[u32; 3]>(['a' as u32, 'b' as u32, 0xD800]);
+
+    let b = writer.buf();
+    let err = <[char; 3]>::try_from_bytes(b, Endianness::default()).unwrap_err();
+    assert!(matches!(
+        err,
+        TryFromBytesError::Element { index: 2, offset: 8, error }
+            if matches!(*error, TryFromBytesError::InvalidChar(0xD800))
+    ));
+}
+
+#[test]
+fn test_tuple_roundtrip() -> Result<(), Error> {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        writer.append::<(u16, u16, u32, u32)>((1, 0, 0x4000, 0x2B0C));
+        writer.append::<(u8, i64)>((7, -7));
+
+        let b = writer.buf();
+        assert_eq!(b.len(), 12 + 9);
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(
+            reader.read::<(u16, u16, u32, u32)>()?.inner(),
+            (1, 0, 0x4000, 0x2B0C)
+        );
+        assert_eq!(reader.read::<(u8, i64)>()?.inner(), (7, -7));
+        assert!(reader.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_tuple_variable_size() -> Result<(), TryFromBytesError> {
+    let mut writer = ByteWriter::new(Endianness::Big);
+    writer.append::<(u8, String, u32, String)>((1, "chomp".into(), 2, "".into()));
+
+    let (value, size) = <(u8, String, u32, String)>::try_from_bytes(writer.buf(), Endianness::Big)?;
+    assert_eq!(value, (1, "chomp".into(), 2, "".into()));
+    assert_eq!(size, 1 + 6 + 4 + 1);
+
+    let err =
+        <(u16, char)>::try_from_bytes(vec![0, 0, 0, 0xD8, 0, 0], Endianness::Little).unwrap_err();
+    assert!(matches!(
+        err,
+        TryFromBytesError::Element {
+            index: 1,
+            offset: 2,
+            ..
+        }
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_f16_bit_patterns() -> Result<(), Error> {
+    let values = [
+        (0x3C00, 1.0),
+        (0xC000, -2.0),
+        (0x7C00, f32::INFINITY),
+        (0xFC00, f32::NEG_INFINITY),
+        (0x7BFF, 65504.0),
+        (0x0001, 5.960_464_5e-8),
+    ];
+    for (bits, value) in values {
+        assert_eq!(f16::from_bits(bits).to_f32(), value);
+        assert_eq!(f16::from_f32(value).to_bits(), bits);
+    }
+    assert!(f16::from_bits(0x7E00).to_f32().is_nan());
+    assert!(f16::from_f32(f32::NAN).is_nan());
+    // ties round to even
+    assert_eq!(
+        f16::from_f32(2049.0).to_bits(),
+        f16::from_f32(2048.0).to_bits()
+    );
+    assert_eq!(f16::from_f32(1e6), f16::INFINITY);
+
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        writer.write_vec(vec![f16::ONE, f16::from_f32(-2.0), f16::INFINITY, f16::NAN]);
+
+        let b = writer.buf();
+        let expected: [u16; 4] = [0x3C00, 0xC000, 0x7C00, 0x7E00];
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(reader.peek_n::<u16>(4)?.flatten(), expected);
+        let halves = reader.read_n::<f16>(4)?.flatten();
+        assert_eq!(
+            halves.iter().map(|h| h.to_bits()).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(f32::from(halves[1]), -2.0);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_nonzero() -> Result<(), Error> {
+    let offsets = [0x10, 0x2B0C, u32::MAX].map(|v| NonZeroU32::new(v).unwrap());
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        writer.append(NonZeroI16::new(-3).unwrap());
+        writer.write_sized_vec(offsets.to_vec());
+        writer.append::<u64>(0);
+
+        let b = writer.buf();
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(reader.read::<NonZeroI16>()?.inner().get(), -3);
+        assert_eq!(reader.read_sized_vector::<NonZeroU32>()?.flatten(), offsets);
+        assert!(matches!(
+            NonZeroU64::try_from_bytes(reader.remaining().into(), endianness),
+            Err(TryFromBytesError::ZeroValue)
+        ));
+        assert!(reader.read::<NonZeroU64>().is_err());
+        assert!(reader.read_n::<NonZeroU8>(1).is_err());
+        assert_eq!(reader.read::<u64>()?.inner(), 0);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_utf16_string() -> Result<(), Error> {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        assert_eq!(writer.append_utf16_string("ab🦀"), 10);
+        writer.append_utf16_string("");
+        writer.append::<u16>(0xBEEF);
+
+        let b = writer.buf();
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(reader.read_utf16_string()?, "ab🦀");
+        assert_eq!(reader.read_utf16_string()?, "");
+        assert_eq!(reader.read::<u16>()?.inner(), 0xBEEF);
+
+        reader.seek(0)?;
+        assert_eq!(reader.read_utf16_string_len(2)?, "ab");
+        assert_eq!(reader.read_utf16_string_len(2)?, "🦀");
+        assert!(reader.read_utf16_string_len(4).is_err());
+        assert_eq!(reader.cursor(), 8);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_utf16_string_errors() -> Result<(), Error> {
+    // lone high surrogate
+    let data = [0x00, 0xD8, 0x61, 0x00, 0x00, 0x00];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(reader.read_utf16_string().is_err());
+    assert_eq!(reader.cursor(), 0);
+
+    // no terminator
+    let data = [0x61, 0x00, 0x62];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(reader.read_utf16_string().is_err());
+    assert_eq!(reader.read_utf16_string_len(1)?, "a");
+    Ok(())
+}
+
+#[test]
+fn test_string_fixed() -> Result<(), Error> {
+    let mut data = b"chomp\0\0\0\0\0\0\0\0\0\0\0".to_vec();
+    data.extend(b"spaced    \0\0\0\0\0\0");
+    data.extend(b"full");
+    data.extend(13u32.to_le_bytes());
+
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert_eq!(reader.read_string_fixed(16)?, "chomp");
+    assert_eq!(reader.cursor(), 16);
+    assert_eq!(reader.peek_n::<u8>(6)?.flatten(), b"spaced");
+    assert_eq!(reader.read_string_fixed_trimmed(16)?, "spaced");
+    assert_eq!(reader.read_string_fixed(4)?, "full");
+    assert_eq!(reader.read::<u32>()?.inner(), 13);
+
+    let data = [0x61, 0xFF, 0x00, 0x00];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(reader.read_string_fixed(8).is_err());
+    assert!(reader.read_string_fixed(4).is_err());
+    assert_eq!(reader.cursor(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_pascal_string() -> Result<(), Error> {
+    let long = "a".repeat(255);
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        assert_eq!(writer.append_pascal_string("chomp")?, 6);
+        writer.append_pascal_string("")?;
+        writer.append_pascal_string(&long)?;
+        assert!(writer.append_pascal_string(&"a".repeat(256)).is_err());
+
+        let b = writer.buf();
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(reader.read_pascal_string()?, "chomp");
+        assert_eq!(reader.read_pascal_string()?, "");
+        assert_eq!(reader.read_pascal_string()?, long);
+        assert!(reader.is_empty());
+        assert!(reader.read_pascal_string().is_err());
+    }
+
+    let data = [0x04, 0x61, 0x62];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(reader.read_pascal_string().is_err());
+    assert_eq!(reader.cursor(), 0);
+
+    let data = [0x02, 0xC0, 0x00];
+    assert!(ByteReader::new(&data, Endianness::Little)
+        .read_pascal_string()
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_read_cstr() -> Result<(), Error> {
+    let data = *b"\0last\0";
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert_eq!(reader.read_cstr()?, "");
+    assert_eq!(reader.read_cstr()?, "last");
+    assert!(reader.is_empty());
+    assert!(matches!(
+        reader.read_cstr().unwrap_err().kind,
+        ErrorKind::UnterminatedString { scanned: 0 }
+    ));
+
+    let data = *b"trunc";
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(matches!(
+        reader.read_cstr().unwrap_err().kind,
+        ErrorKind::UnterminatedString { scanned: 5 }
+    ));
+    assert_eq!(reader.cursor(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_sized_vector_with() -> Result<(), Error> {
+    let values: Vec<u32> = vec![1, 0xFFFF, 0xDEADBEEF];
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        assert_eq!(writer.write_sized_vec_with::<u16, u32>(values.clone())?, 14);
+        assert_eq!(writer.write_sized_vec_with::<u64, u32>(values.clone())?, 20);
+        assert!(writer.write_sized_vec_with::<u8, u8>(vec![0; 256]).is_err());
+
+        let b = writer.buf();
+        assert_eq!(b.len(), 34);
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(
+            reader.read_sized_vector_with::<u16, u32>()?.flatten(),
+            values
+        );
+        assert_eq!(
+            reader.read_sized_vector_with::<u64, u32>()?.flatten(),
+            values
+        );
+        assert!(reader.is_empty());
+    }
+
+    let data = [0xFF, 0x00];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(matches!(
+        reader.read_sized_vector_with::<i8, u8>(),
+        Err(Error {
+            kind: ErrorKind::LengthOverflow,
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_varint() -> Result<(), Error> {
+    let data = [
+        0x00, 0x7F, 0xAC, 0x02, 0xE5, 0x8E, 0x26, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01,
+    ];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert_eq!(reader.read_varint::<u8>()?, 0);
+    assert_eq!(reader.read_varint::<u8>()?, 0x7F);
+    assert_eq!(reader.read_varint::<u16>()?, 300);
+    assert_eq!(reader.read_varint::<u32>()?, 624485);
+    assert_eq!(reader.read_varint::<u32>()?, u32::MAX);
+    assert_eq!(reader.read_varint::<u64>()?, u64::MAX);
+    assert!(reader.is_empty());
+
+    let mut writer = ByteWriter::new(Endianness::Big);
+    for value in [0, 0x7F, 300, 624485, u32::MAX as u64, u64::MAX] {
+        writer.append_varint(value);
+    }
+    assert_eq!(writer.buf(), data);
+    Ok(())
+}
+
+#[test]
+fn test_varint_errors() {
+    let is_overflow = |data: &[u8]| {
+        let mut reader = ByteReader::new(data, Endianness::Little);
+        let res = reader.read_varint::<u32>();
+        assert_eq!(reader.cursor() == 0, res.is_err());
+        matches!(
+            res,
+            Err(Error {
+                kind: ErrorKind::VarintOverflow,
+                ..
+            })
+        )
+    };
+    // u32::MAX + 1
+    assert!(is_overflow(&[0x80, 0x80, 0x80, 0x80, 0x10]));
+    // six bytes is too long for a u32, even if the value is small
+    assert!(is_overflow(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]));
+    assert!(!is_overflow(&[0x80, 0x80, 0x80, 0x80, 0x00]));
+
+    let mut reader = ByteReader::new(&[0xAC], Endianness::Little);
+    assert!(matches!(
+        reader.read_varint::<u64>(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 2,
+                available: 1,
+                type_name: "u64"
+            },
+            position: Some(0),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_varint_zigzag() -> Result<(), Error> {
+    let values = [0, -1, 1, -2, 2, i64::MAX, i64::MIN];
+    let mut writer = ByteWriter::new(Endianness::Little);
+    for value in values {
+        writer.append_varint_zigzag(value);
+    }
+    let b = writer.buf();
+    assert_eq!(b[..5], [0x00, 0x01, 0x02, 0x03, 0x04]);
+
+    let mut reader = ByteReader::new(&b, Endianness::Little);
+    for value in values {
+        assert_eq!(reader.read_varint_zigzag::<i64>()?, value);
+    }
+    assert!(reader.is_empty());
+
+    let mut writer = ByteWriter::new(Endianness::Little);
+    writer.append_varint_zigzag(i32::MIN);
+    writer.append_varint_zigzag(i32::MAX);
+    let b = writer.buf();
+    let mut reader = ByteReader::new(&b, Endianness::Little);
+    assert_eq!(reader.read_varint_zigzag::<i32>()?, i32::MIN);
+    assert_eq!(reader.read_varint_zigzag::<i32>()?, i32::MAX);
+
+    // an over-long encoding of 0
+    let data = [0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(matches!(
+        reader.read_varint_zigzag::<i32>(),
+        Err(Error {
+            kind: ErrorKind::VarintOverflow,
+            position: Some(0),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_vlq() -> Result<(), Error> {
+    let examples: [(u32, &[u8]); 8] = [
+        (0x00, &[0x00]),
+        (0x40, &[0x40]),
+        (0x7F, &[0x7F]),
+        (0x80, &[0x81, 0x00]),
+        (0x2000, &[0xC0, 0x00]),
+        (0x3FFF, &[0xFF, 0x7F]),
+        (0x4000, &[0x81, 0x80, 0x00]),
+        (0x0FFF_FFFF, &[0xFF, 0xFF, 0xFF, 0x7F]),
+    ];
+    for (value, bytes) in examples {
+        let mut writer = ByteWriter::new(Endianness::Little);
+        assert_eq!(writer.write_vlq(value)?, bytes.len());
+        assert_eq!(writer.buf(), bytes);
+        assert_eq!(ByteReader::new(bytes, Endianness::Big).read_vlq()?, value);
+    }
+    assert!(ByteWriter::new(Endianness::Little)
+        .write_vlq(0x1000_0000)
+        .is_err());
+
+    let mut reader = ByteReader::new(&[0xFF, 0xFF, 0xFF, 0xFF, 0x7F], Endianness::Little);
+    assert!(matches!(
+        reader.read_vlq(),
+        Err(Error {
+            kind: ErrorKind::VarintOverflow,
+            position: Some(0),
+            ..
+        })
+    ));
+    let mut reader = ByteReader::new(&[0x81, 0x80], Endianness::Little);
+    assert!(matches!(
+        reader.read_vlq(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 3,
+                available: 2,
+                ..
+            },
+            position: Some(0),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_bitreader() -> Result<(), Error> {
+    // 3, 5, 8 and 4 bit fields followed by a byte aligned u16
+    let data = [0b1011_0011, 0b1010_0101, 0b1100_0000, 0x34, 0x12];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+
+    let mut bits = BitReader::new(&mut reader);
+    assert_eq!(bits.read_bits(3)?, 0b101);
+    assert_eq!(bits.read_bits(5)?, 0b10011);
+    assert_eq!(bits.read_bits(8)?, 0xA5);
+    assert!(bits.read_bit()?);
+    assert_eq!(bits.read_bits(3)?, 0b100);
+    assert_eq!(bits.bit_position(), 20);
+    assert_eq!(bits.align_to_byte(), 4);
+    assert_eq!(bits.align_to_byte(), 0);
+
+    assert_eq!(reader.cursor(), 3);
+    assert_eq!(reader.read::<u16>()?.inner(), 0x1234);
+    Ok(())
+}
+
+#[test]
+fn test_bitreader_spanning_reads() -> Result<(), Error> {
+    let data = [0xDE, 0xAD, 0xBE, 0xEF, 0x01];
+    let mut bits = BitReader::from_slice(&data);
+    assert_eq!(bits.read_bits(4)?, 0xD);
+    assert_eq!(bits.read_bits(13)?, 0b1_1101_0101_1011);
+    assert_eq!(bits.read_bits(15)?, 0b011_1110_1110_1111);
+    assert!(matches!(
+        bits.read_bits(9),
+        Err(Error {
+            kind: ErrorKind::NoBits { bit: 32 },
+            ..
+        })
+    ));
+    assert_eq!(bits.read_bits(8)?, 0x01);
+    assert_eq!(bits.into_inner().cursor(), 5);
+
+    let mut bits = BitReader::from_slice(&[0b0000_0110, 0x01]).with_bit_order(BitOrder::LsbFirst);
+    assert!(!bits.read_bit()?);
+    assert_eq!(bits.read_bits(2)?, 0b11);
+    bits.align_to_byte();
+    assert_eq!(bits.read_bits(8)?, 0x01);
+    Ok(())
+}
+
+#[test]
+fn test_bitwriter_default() {
+    let mut bits = BitWriter::default();
+    assert_eq!(bits.bit_position(), 0);
+    bits.write_bit(true);
+    bits.write_bits(0b01, 2);
+    assert_eq!(bits.bit_position(), 3);
+    assert_eq!(bits.into_bytes(), vec![0b1010_0000]);
+}
+
+#[derive(ChompEnum, Debug, PartialEq, Clone, Copy)]
+#[repr(u8)]
+enum Format {
+    Rgba8,
+    Bc1 = 0x47,
+    Bc3,
+    Bc7 = 0x62,
+}
+
+#[derive(ChompEnum, Debug, PartialEq, Clone, Copy)]
+#[repr(u16)]
+enum Command {
+    Open = 0x0100,
+    Close = 0x0200,
+}
+
+#[test]
+fn test_enum_roundtrip() -> Result<(), Error> {
+    let formats = vec![Format::Rgba8, Format::Bc1, Format::Bc3, Format::Bc7];
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        writer.write_sized_vec(formats.clone());
+        writer.write_vec(vec![Command::Close, Command::Open]);
+
+        let b = writer.buf();
+        assert_eq!(b[4..8], [0x00, 0x47, 0x48, 0x62]);
+        let mut reader = ByteReader::new(&b, endianness);
+        assert_eq!(reader.read_sized_vector::<Format>()?.flatten(), formats);
+        assert_eq!(reader.read::<Command>()?.inner(), Command::Close);
+        assert_eq!(reader.read_n::<Command>(1)?.flatten(), vec![Command::Open]);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_enum_invalid_discriminant() {
+    let data = [0x01, 0x00, 0x03, 0x00];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(reader.read::<Format>().is_err());
+    assert!(reader.read::<Command>().is_err());
+    assert_eq!(reader.cursor(), 0);
+
+    let err = Command::try_from_bytes(data[2..].to_vec(), Endianness::Little).unwrap_err();
+    assert!(matches!(
+        err,
+        TryFromBytesError::InvalidDiscriminant {
+            value: 3,
+            type_name
+        } if type_name.ends_with("Command")
+    ));
+}
+
+#[derive(TryFromBytes, Debug, PartialEq, Clone)]
+struct Dimensions(u16, u16);
+
+#[derive(TryFromBytes, Debug, PartialEq, Clone)]
+struct TextureHeader {
+    version: u16,
+    flags: u16,
+    data_size: u32,
+    file_size: u32,
+    reserved: u32,
+    dimensions: Dimensions,
+    format: u16,
+    padding: [u8; 6],
+    mip_offsets: [u32; 14],
+}
+
+#[test]
+fn test_derive_try_from_bytes() -> Result<(), Error> {
+    let data = std::fs::read("test/texture.text")?;
+    let mut reader = ByteReader::new(&data, Endianness::default());
+    let header = reader.read::<TextureHeader>()?.inner();
+    assert_eq!(
+        header,
+        TextureHeader {
+            version: 1,
+            flags: 0,
+            data_size: 0x4000,
+            file_size: 0x2B0C,
+            reserved: 0,
+            dimensions: Dimensions(0x80, 0x80),
+            format: 0x49,
+            padding: [0; 6],
+            mip_offsets: [8192, 10240, 10752, 10880, 10912, 10920, 10928, 10936, 0, 0, 0, 0, 0, 0],
+        }
+    );
+    assert_eq!(reader.cursor(), 0x1c + 14 * 4);
+    Ok(())
+}
+
+#[derive(TryFromBytes, Debug)]
+struct Glyph {
+    #[allow(dead_code)]
+    width: u8,
+    #[allow(dead_code)]
+    code: char,
+}
+
+#[test]
+fn test_derive_try_from_bytes_error() {
+    let data = vec![0x08, 0x00, 0xD8, 0x00, 0x00];
+    let err = Glyph::try_from_bytes(data, Endianness::Little).unwrap_err();
+    assert!(matches!(
+        err,
+        TryFromBytesError::Field {
+            name: "code",
+            offset: 1,
+            error
+        } if matches!(*error, TryFromBytesError::InvalidChar(0xD800))
+    ));
+    let data = vec![0x08, 0x00];
+    assert!(matches!(
+        Glyph::try_from_bytes(data, Endianness::Little),
+        Err(TryFromBytesError::Field { name: "code", .. })
+    ));
+}
+
+#[derive(TryFromBytes, ToBytes, Debug, PartialEq, Clone)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(TryFromBytes, ToBytes, Debug, PartialEq, Clone)]
+struct Label {
+    id: u16,
+    name: String,
+    position: Point,
+    visible: bool,
+}
+
+#[test]
+fn test_derive_to_bytes() -> Result<(), Error> {
+    let label = Label {
+        id: 0x1234,
+        name: "origin".to_string(),
+        position: Point { x: -1, y: 0x100 },
+        visible: true,
+    };
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        let size = writer.append::<Label>(label.clone());
+        assert_eq!(size, 2 + 7 + 8 + 1);
+        assert_eq!(writer.len(), size);
+
+        let (read, read_size) = Label::try_from_bytes(writer.buf(), endianness).unwrap();
+        assert_eq!(read, label);
+        assert_eq!(read_size, size);
+    }
+
+    let mut writer = ByteWriter::new(Endianness::Big);
+    writer.append::<Point>(Point { x: 1, y: 2 });
+    assert_eq!(writer.buf(), vec![0, 0, 0, 1, 0, 0, 0, 2]);
+    Ok(())
+}
+
+#[test]
+fn test_optional() -> Result<(), Error> {
+    let mut writer = ByteWriter::new(Endianness::Big);
+    assert_eq!(writer.append_optional::<u16>(Some(0x1234)), 3);
+    assert_eq!(writer.append_optional::<u16>(None), 1);
+    assert_eq!(writer.append_optional::<char>(Some('c')), 5);
+    let buf = writer.buf();
+    assert_eq!(buf[..4], [0x01, 0x12, 0x34, 0x00]);
+
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    assert_eq!(reader.read_optional::<u16>()?, Some(0x1234));
+    assert_eq!(reader.read_optional::<u16>()?, None);
+    assert_eq!(reader.cursor(), 4);
+    assert_eq!(reader.read_optional::<char>()?, Some('c'));
+    assert!(reader.is_empty());
+
+    // the flag is set but the payload is truncated
+    let buf = [0x00, 0x01, 0xAB];
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    assert_eq!(reader.read_optional::<u16>()?, None);
+    assert!(matches!(
+        reader.read_optional::<u16>(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_read_bytes() -> Result<(), Error> {
+    let buf = [0xDE, 0xAD, 0xBE, 0xEF, 0x01];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read_bytes::<0>()?, []);
+    assert_eq!(reader.cursor(), 0);
+    assert_eq!(reader.peek_bytes::<4>()?, [0xDE, 0xAD, 0xBE, 0xEF]);
+    assert_eq!(reader.read_bytes::<4>()?, [0xDE, 0xAD, 0xBE, 0xEF]);
+    assert_eq!(reader.cursor(), 4);
+    assert!(matches!(
+        reader.read_bytes::<2>(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 2,
+                available: 1,
+                type_name: "[u8; 2]",
+            },
+            position: Some(4),
+            ..
+        })
+    ));
+    assert_eq!(reader.read_bytes::<1>()?, [0x01]);
+    Ok(())
+}
+
+#[test]
+fn test_read_until() -> Result<(), Error> {
+    let buf = [0x0A, 0x41, 0x42, 0x0A, 0x43, 0x0A];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    // the delimiter comes first
+    assert_eq!(reader.read_until(0x0A)?, []);
+    assert_eq!(reader.cursor(), 1);
+    assert_eq!(reader.read_until(0x0A)?, b"AB");
+    // the delimiter is the last byte
+    assert_eq!(reader.read_until(0x0A)?, b"C");
+    assert!(reader.is_empty());
+
+    let buf = [0x01, 0x02, 0x03];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_until(0xFF),
+        Err(Error {
+            kind: ErrorKind::NoDelimiter { delimiter: 0xFF },
+            position: Some(0),
+            ..
+        })
+    ));
+    assert_eq!(reader.read_until_or_eof(0xFF), [0x01, 0x02, 0x03]);
+    assert_eq!(reader.read_until_or_eof(0xFF), []);
+    Ok(())
+}
+
+#[test]
+fn test_read_line() -> Result<(), Error> {
+    let mut buf = b"ply\r\nelement vertex 2\n".to_vec();
+    buf.extend_from_slice(&0xCAFEu32.to_le_bytes());
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read_line()?, "ply");
+    assert_eq!(reader.read_line()?, "element vertex 2");
+    assert_eq!(reader.read::<u32>()?.inner(), 0xCAFE);
+
+    let buf = *b"ok\n\xFF\xFE\n";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read_line()?, "ok");
+    assert!(matches!(
+        reader.read_line(),
+        Err(Error {
+            kind: ErrorKind::InvalidUtf8 { offset: 3, .. },
+            position: Some(3),
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_bytewriter_write() -> Result<(), Error> {
+    let mut writer = ByteWriter::new(Endianness::Little);
+    writer.append::<u32>(0);
+    writer.append::<u32>(0);
+
+    // overwrites in place, including right up to the end
+    assert_eq!(writer.write::<u16>(0xBEEF, 1)?, 2);
+    assert_eq!(writer.write::<u32>(0x12345678, 4)?, 4);
+    assert_eq!(writer.write::<[u8; 0]>([], 8)?, 0);
+    assert_eq!(writer.buf(), [0, 0xEF, 0xBE, 0, 0x78, 0x56, 0x34, 0x12]);
+
+    // starting past the end
+    assert!(matches!(
+        writer.write::<u8>(1, 9),
+        Err(Error {
+            kind: ErrorKind::Write(WriteErrorKind::WriteOutOfRange {
+                position: 9,
+                len: 1,
+                buf_len: 8
+            }),
+            position: Some(9),
+            ..
+        })
+    ));
+    // starting inside but spilling past the end
+    let err = writer.write::<u32>(0xFFFFFFFF, 6).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        ErrorKind::Write(WriteErrorKind::WriteOutOfRange {
+            position: 6,
+            len: 4,
+            buf_len: 8
+        })
+    ));
+    assert_eq!(
+        err.to_string(),
+        "write of 4 bytes at 0x6 is past the end of the buffer of length 0x8 at 0x6"
+    );
+    assert!(writer.write::<u8>(1, usize::MAX).is_err());
+    // failed writes leave the buffer as it was
+    assert_eq!(writer.buf(), [0, 0xEF, 0xBE, 0, 0x78, 0x56, 0x34, 0x12]);
+    Ok(())
+}
+
+#[test]
+fn test_padded_string() -> Result<(), Error> {
+    let mut writer = ByteWriter::new(Endianness::Little);
+    assert_eq!(writer.append_padded_string("bin/ls", 8, 0x00)?, 8);
+    // exactly fills the field, so there is no terminator
+    assert_eq!(writer.append_padded_string("etc/init", 8, 0x00)?, 8);
+    assert_eq!(writer.append_padded_string("usr", 8, b' ')?, 8);
+    assert!(matches!(
+        writer.append_padded_string("too/long/name", 8, 0x00),
+        Err(Error {
+            kind: ErrorKind::Write(WriteErrorKind::StringTooLong { len: 13, max: 8 }),
+            position: Some(24),
+            ..
+        })
+    ));
+    let buf = writer.buf();
+    assert_eq!(buf.len(), 24);
+
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read_padded_string(8, true)?, "bin/ls");
+    assert_eq!(reader.read_padded_string(8, true)?, "etc/init");
+    assert_eq!(reader.read_padded_string(8, true)?, "usr     ");
+
+    let buf = *b"a\0\0b\0\0\0\0";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_padded_string(8, true),
+        Err(Error {
+            kind: ErrorKind::Decode(TryFromBytesError::NonZeroPadding {
+                offset: 3,
+                byte: b'b'
+            }),
+            position: Some(0),
+            ..
+        })
+    ));
+    assert_eq!(reader.read_padded_string(8, false)?, "a");
+    assert!(reader.is_empty());
+
+    // offsets are in the original buffer even when the reader is rebased
+    let buf = *b"\xFFa\0b\0";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.skip_bytes(1)?;
+    reader.rebase_to_cursor();
+    assert!(matches!(
+        reader.read_padded_string(4, true),
+        Err(Error {
+            kind: ErrorKind::Decode(TryFromBytesError::NonZeroPadding {
+                offset: 3,
+                byte: b'b'
+            }),
+            position: Some(1),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "encodings")]
+#[test]
+fn test_string_encoded() -> Result<(), Error> {
+    use encoding_rs::{REPLACEMENT, SHIFT_JIS, UTF_16LE, WINDOWS_1252};
+
+    let data = std::fs::read("test/shift_jis.text")?;
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert_eq!(reader.read_string_encoded(SHIFT_JIS)?, "こんにちは");
+    assert_eq!(reader.read_string_encoded_fixed(SHIFT_JIS, 8)?, "ﾃｽﾄ");
+    // offsets stay in the original buffer after a rebase
+    reader.rebase_to_cursor();
+    assert!(matches!(
+        reader.read_string_encoded(SHIFT_JIS),
+        Err(Error {
+            kind: ErrorKind::InvalidEncoding {
+                encoding: "Shift_JIS",
+                offset: 19
+            },
+            position: Some(19),
+            ..
+        })
+    ));
+
+    let mut writer = ByteWriter::new(Endianness::Little);
+    assert_eq!(writer.append_string_encoded("こんにちは", SHIFT_JIS)?, 11);
+    assert_eq!(writer.buf(), data[..11]);
+    assert!(matches!(
+        writer.append_string_encoded("€ ☃", WINDOWS_1252),
+        Err(Error {
+            kind: ErrorKind::Write(WriteErrorKind::UnmappableChar { ch: '☃', .. }),
+            ..
+        })
+    ));
+
+    // UTF-16 strings can't end in a single NUL, and its encoder would write UTF-8
+    let buf = [b'h', 0x00, b'i', 0x00, 0x00, 0x00];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_string_encoded(UTF_16LE),
+        Err(Error {
+            kind: ErrorKind::UnsupportedEncoding {
+                encoding: "UTF-16LE"
+            },
+            position: Some(0),
+            ..
+        })
+    ));
+    assert!(reader.read_string_encoded_fixed(UTF_16LE, 6).is_err());
+    assert_eq!(reader.cursor(), 0);
+    let mut writer = ByteWriter::new(Endianness::Little);
+    assert!(matches!(
+        writer.append_string_encoded("hi", UTF_16LE),
+        Err(Error {
+            kind: ErrorKind::Write(WriteErrorKind::UnsupportedEncoding {
+                encoding: "UTF-16LE"
+            }),
+            ..
+        })
+    ));
+    assert!(writer.append_string_encoded("hi", REPLACEMENT).is_err());
+    assert!(writer.buf().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_net() -> Result<(), Error> {
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
+    // a little endian record with a big endian port, as in a packet capture
+    let buf = [
+        0x02, 0x00, // record type
+        192, 168, 1, 20, // source address
+        0x1F, 0x90, // port 8080
+        0x01, 0x00, 0x00, 0x00, // sequence
+    ];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read::<u16>()?.inner(), 2);
+    let ip = reader.read::<Ipv4Addr>()?.inner();
+    assert_eq!(ip, Ipv4Addr::new(192, 168, 1, 20));
+    let port = ByteReader::new(reader.remaining(), Endianness::Big)
+        .read::<u16>()?
+        .inner();
+    assert_eq!(port, 8080);
+    reader.seek(8)?;
+    assert_eq!(reader.read::<u32>()?.inner(), 1);
+
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let v4 = SocketAddrV4::new(ip, 8080);
+        let v6 = SocketAddrV6::new(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), 443, 0, 0);
+        let mut writer = ByteWriter::new(endianness);
+        assert_eq!(writer.append(v4), 6);
+        assert_eq!(writer.append(v6), 18);
+        let buf = writer.buf();
+        assert_eq!(buf[..4], [192, 168, 1, 20]);
+        assert_eq!(buf[6..8], [0x20, 0x01]);
+
+        let mut reader = ByteReader::new(&buf, endianness);
+        assert_eq!(reader.read::<SocketAddrV4>()?.inner(), v4);
+        assert_eq!(reader.read::<SocketAddrV6>()?.inner(), v6);
+        assert!(reader.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_unix_timestamp() -> Result<(), Error> {
+    use std::time::UNIX_EPOCH;
+
+    // 2024-02-29T12:00:00Z
+    let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_208_000);
+    let last_u32 = UNIX_EPOCH + Duration::from_secs(u32::MAX as u64);
+
+    let mut writer = ByteWriter::new(Endianness::Big);
+    assert_eq!(writer.append_unix_timestamp_secs::<u32>(UNIX_EPOCH)?, 4);
+    writer.append_unix_timestamp_secs::<u32>(leap_day + Duration::from_millis(250))?;
+    writer.append_unix_timestamp_secs::<u32>(last_u32)?;
+    assert_eq!(writer.append_unix_timestamp_millis::<u64>(leap_day)?, 8);
+    // 2106 doesn't fit in a u32, but does in a u64
+    let overflow = last_u32 + Duration::from_secs(1);
+    assert!(matches!(
+        writer.append_unix_timestamp_secs::<u32>(overflow),
+        Err(Error {
+            kind: ErrorKind::Write(WriteErrorKind::TimestampOutOfRange { .. }),
+            ..
+        })
+    ));
+    writer.append_unix_timestamp_secs::<u64>(overflow)?;
+    assert!(matches!(
+        writer.append_unix_timestamp_secs::<u64>(UNIX_EPOCH - Duration::from_secs(1)),
+        Err(Error {
+            kind: ErrorKind::Write(WriteErrorKind::TimestampOutOfRange { .. }),
+            ..
+        })
+    ));
+    let buf = writer.buf();
+    assert_eq!(buf[4..8], [0x65, 0xE0, 0x71, 0xC0]);
+
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    assert_eq!(reader.read_unix_timestamp_secs::<u32>()?, UNIX_EPOCH);
+    assert_eq!(reader.read_unix_timestamp_secs::<u32>()?, leap_day);
+    assert_eq!(reader.read_unix_timestamp_secs::<u32>()?, last_u32);
+    assert_eq!(reader.read_unix_timestamp_millis::<u64>()?, leap_day);
+    assert_eq!(reader.read_unix_timestamp_secs::<u64>()?, overflow);
+
+    let buf = u64::MAX.to_be_bytes();
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    assert!(matches!(
+        reader.read_unix_timestamp_secs::<u64>(),
+        Err(Error {
+            kind: ErrorKind::TimestampOutOfRange,
+            position: Some(0),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_uuid() -> Result<(), Error> {
+    let bytes = [
+        0x12, 0x3E, 0x45, 0x67, 0xE8, 0x9B, 0x12, 0xD3, 0xA4, 0x56, 0x42, 0x66, 0x14, 0x17, 0x40,
+        0x00,
+    ];
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut buf = bytes.to_vec();
+        buf.push(0xFF);
+        let mut reader = ByteReader::new(&buf, endianness);
+        let uuid = reader.read_uuid()?;
+        assert_eq!(uuid.to_string(), "123e4567-e89b-12d3-a456-426614174000");
+        assert_eq!(reader.cursor(), 16);
+
+        let mut writer = ByteWriter::new(endianness);
+        assert_eq!(writer.append_uuid(uuid), 16);
+        assert_eq!(writer.buf(), bytes);
+    }
+    let mut reader = ByteReader::new(&bytes[..15], Endianness::Little);
+    assert!(reader.read_uuid().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_bcd() -> Result<(), Error> {
+    let mut writer = ByteWriter::new(Endianness::Little);
+    assert_eq!(writer.write_bcd(42, 3)?, 3);
+    writer.write_bcd(9999, 2)?;
+    writer.write_bcd(u64::MAX, 10)?;
+    assert!(matches!(
+        writer.write_bcd(10000, 2),
+        Err(Error {
+            kind: ErrorKind::Write(WriteErrorKind::BcdOverflow {
+                value: 10000,
+                len: 2
+            }),
+            ..
+        })
+    ));
+    let buf = writer.buf();
+    assert_eq!(buf[..5], [0x00, 0x00, 0x42, 0x99, 0x99]);
+
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.peek_bytes::<3>()?, [0x00, 0x00, 0x42]);
+    assert_eq!(reader.read_bcd_string(3)?, "000042");
+    assert_eq!(reader.read_bcd(2)?, 9999);
+    assert_eq!(reader.read_bcd(10)?, u64::MAX);
+
+    let buf = [0x99; 10];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_bcd(10),
+        Err(Error {
+            kind: ErrorKind::BcdOverflow,
+            ..
+        })
+    ));
+    assert_eq!(reader.read_bcd_string(10)?, "9".repeat(20));
+
+    let buf = [0x12, 0x3A, 0x45];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_bcd(3),
+        Err(Error {
+            kind: ErrorKind::InvalidBcd {
+                offset: 1,
+                nibble: 0xA
+            },
+            position: Some(0),
+            ..
+        })
+    ));
+
+    // offsets are in the original buffer even when the reader is rebased
+    reader.skip_bytes(1)?;
+    reader.rebase_to_cursor();
+    assert!(matches!(
+        reader.read_bcd(2),
+        Err(Error {
+            kind: ErrorKind::InvalidBcd {
+                offset: 1,
+                nibble: 0xA
+            },
+            position: Some(1),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_guid_le() -> Result<(), Error> {
+    // the display adapter device class, as stored under HKLM\SYSTEM\...\Class
+    let bytes = [
+        0x68, 0xE9, 0x36, 0x4D, 0x25, 0xE3, 0xCE, 0x11, 0xBF, 0xC1, 0x08, 0x00, 0x2B, 0xE1, 0x03,
+        0x18,
+    ];
+    let mut reader = ByteReader::new(&bytes, Endianness::Little);
+    let guid = reader.read_guid_le()?;
+    assert_eq!(guid.to_string(), "4d36e968-e325-11ce-bfc1-08002be10318");
+    reader.seek(0)?;
+    assert_eq!(
+        reader.read_uuid()?.to_string(),
+        "68e9364d-25e3-ce11-bfc1-08002be10318"
+    );
+
+    let mut writer = ByteWriter::new(Endianness::Big);
+    assert_eq!(writer.append_guid_le(guid), 16);
+    assert_eq!(writer.buf(), bytes);
+    Ok(())
+}
+
+#[test]
+fn test_read_float_slice() -> Result<(), Error> {
+    let mut writer = ByteWriter::new(Endianness::Big);
+    writer.append::<f32>(1.5);
+    writer.append::<f32>(-0.25);
+    writer.append::<f64>(std::f64::consts::PI);
+    let buf = writer.buf();
+
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    assert_eq!(reader.read_f32_slice(2)?, vec![1.5, -0.25]);
+    assert!(reader.read_f64_slice(2).is_err());
+    assert_eq!(reader.cursor(), 8);
+    assert_eq!(reader.read_f64_slice(1)?, vec![std::f64::consts::PI]);
+    assert!(reader.read_f32_slice(usize::MAX).is_err());
+    Ok(())
+}
+
+#[test]
+fn benchmark_read_float_slice() -> Result<(), Error> {
+    let n = 1 << 20;
+    let data: Vec<u8> = (0..n).flat_map(|i| (i as f32).to_le_bytes()).collect();
+    let mut timer = Timer::new();
+
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    let generic = reader.read_n::<f32>(n)?.flatten();
+    let generic_time = timer.time();
+    println!(
+        "reader.read_n::<f32>({})?.flatten(): {:#?}",
+        n, generic_time
+    );
+
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    let bulk = reader.read_f32_slice(n)?;
+    let bulk_time = timer.time();
+    println!("reader.read_f32_slice({})?: {:#?}", n, bulk_time);
+
+    assert_eq!(generic, bulk);
+    Ok(())
+}
+
+#[derive(TryFromBytes, ToBytes, Debug, PartialEq, Clone)]
+struct PacketHeader {
+    #[bits(4)]
+    version: u8,
+    #[bits(1)]
+    compressed: bool,
+    #[bits(11)]
+    length: u16,
+    sequence: u16,
+}
+
+#[derive(TryFromBytes, ToBytes, Debug, PartialEq, Clone)]
+#[chomp(bit_order = "lsb_first")]
+struct LsbFlags {
+    #[bits(3)]
+    kind: u8,
+    #[bits(5)]
+    flags: u8,
+}
+
+#[test]
+fn test_derive_bits() -> Result<(), Error> {
+    let header = PacketHeader {
+        version: 0x5,
+        compressed: true,
+        length: 0x2A3,
+        sequence: 0x1234,
+    };
+    assert_eq!(PacketHeader::SIZE, 4);
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let mut writer = ByteWriter::new(endianness);
+        assert_eq!(writer.append(header.clone()), 4);
+        let buf = writer.buf();
+        // 0101 1 01010100011
+        assert_eq!(buf[..2], [0b0101_1010, 0b1010_0011]);
+
+        let mut reader = ByteReader::new(&buf, endianness);
+        assert_eq!(reader.read::<PacketHeader>()?.inner(), header);
+    }
+
+    let flags = LsbFlags {
+        kind: 0b110,
+        flags: 0b10011,
+    };
+    let bytes = flags.to_bytes(Endianness::Little);
+    assert_eq!(bytes, vec![0b1001_1110]);
+    assert_eq!(
+        LsbFlags::try_from_bytes(bytes, Endianness::Little)
+            .unwrap()
+            .0,
+        flags
+    );
+
+    assert!(matches!(
+        PacketHeader::try_from_bytes(vec![0x00], Endianness::Little),
+        Err(TryFromBytesError::Field {
+            name: "version",
+            offset: 0,
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[derive(ChompEnum, Debug, PartialEq, Clone, Copy)]
+#[repr(u16)]
+enum MessageKind {
+    Ping = 1,
+    Pong,
+    #[chomp(other)]
+    Unknown(u16),
+}
+
+#[test]
+fn test_chomp_enum_other() -> Result<(), Error> {
+    let mut writer = ByteWriter::new(Endianness::Big);
+    writer.append(MessageKind::Pong);
+    writer.append(MessageKind::Unknown(0x0300));
+    let mut buf = writer.buf();
+    assert_eq!(buf, vec![0x00, 0x02, 0x03, 0x00]);
+    buf.push(0x01);
+
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    assert_eq!(reader.read::<MessageKind>()?.inner(), MessageKind::Pong);
+    assert_eq!(
+        reader.read::<MessageKind>()?.inner(),
+        MessageKind::Unknown(0x0300)
+    );
+    assert!(matches!(
+        reader.read::<MessageKind>().map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            ..
+        })
+    ));
+    assert_eq!(
+        MessageKind::try_from_bytes(vec![0x00, 0x01], Endianness::Big).unwrap(),
+        (MessageKind::Ping, 2)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_read_prefixed() -> Result<(), Error> {
+    // an outer block holding a u16, a nested block of two u8s and a spare byte, then a trailer
+    let buf = [0x07, 0x34, 0x12, 0x02, 0x00, 0x0A, 0x0B, 0xEE, 0xFF];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    let parse = |block: &mut ByteReader| {
+        let value = block.read::<u16>()?.inner();
+        let nested =
+            block.read_prefixed_strict::<u16, _>(|nested| Ok(nested.read_n::<u8>(2)?.flatten()))?;
+        Ok((value, nested))
+    };
+    // the outer block has a byte left over, which strict mode rejects
+    assert!(matches!(
+        reader.read_prefixed_strict::<u8, _>(parse),
+        Err(Error {
+            kind: ErrorKind::UnconsumedBytes { remaining: 1 },
+            position: Some(0),
+            ..
+        })
+    ));
+    let (value, nested) = reader.read_prefixed::<u8, _>(parse)?;
+    assert_eq!(value, 0x1234);
+    assert_eq!(nested, vec![0x0A, 0x0B]);
+    assert_eq!(reader.cursor(), 8);
+    reader.seek(0)?;
+
+    // lenient mode skips whatever wasn't read
+    let value = reader.read_prefixed::<u8, _>(|block| Ok(block.read::<u16>()?.inner()))?;
+    assert_eq!(value, 0x1234);
+    assert_eq!(reader.cursor(), 8);
+    assert_eq!(reader.read::<u8>()?.inner(), 0xFF);
+
+    let buf = [0x02, 0x00, 0x01];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_prefixed_strict::<u8, _>(|block| Ok(block.read::<u8>()?.inner())),
+        Err(Error {
+            kind: ErrorKind::UnconsumedBytes { remaining: 1 },
+            position: Some(0),
+            ..
+        })
+    ));
+    // the block can't be read past its end
+    assert!(reader
+        .read_prefixed::<u8, _>(|block| Ok(block.read::<u32>()?.inner()))
+        .is_err());
+    // a length longer than the buffer
+    let buf = [0x05, 0x00, 0x01];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_prefixed::<u8, _>(|_| Ok(())),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(0),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[derive(TryFromBytes, ToBytes, Debug, PartialEq, Clone)]
+struct PaddedTextureHeader {
+    version: u16,
+    #[align(4)]
+    data_size: u32,
+    file_size: u32,
+    #[pad(4, strict)]
+    width: u16,
+    height: u16,
+    format: u16,
+    #[align(4)]
+    #[pad(4)]
+    mip_offsets: [u32; 14],
+}
+
+#[test]
+fn test_derive_padding() -> Result<(), Error> {
+    let data = std::fs::read("test/texture.text")?;
+    let mut reader = ByteReader::new(&data, Endianness::default());
+    let header = reader.read::<PaddedTextureHeader>()?.inner();
+
+    // the same fields, read by hand
+    let mut manual = ByteReader::new(&data, Endianness::default());
+    assert_eq!(header.version, manual.read::<u16>()?.inner());
+    manual.seek(4)?;
+    assert_eq!(header.data_size, manual.read::<u32>()?.inner());
+    assert_eq!(header.file_size, manual.read::<u32>()?.inner());
+    manual.seek(16)?;
+    assert_eq!(header.width, manual.read::<u16>()?.inner());
+    assert_eq!(header.height, manual.read::<u16>()?.inner());
+    assert_eq!(header.format, manual.read::<u16>()?.inner());
+    manual.seek(0x1c)?;
+    assert_eq!(header.mip_offsets, manual.read::<[u32; 14]>()?.inner());
+    assert_eq!(reader.cursor(), manual.cursor());
+    assert_eq!(PaddedTextureHeader::SIZE, manual.cursor());
+
+    // padding is written as zeros, so only the skipped fields differ
+    let bytes = header.to_bytes(Endianness::default());
+    assert_eq!(bytes.len(), PaddedTextureHeader::SIZE);
+    assert_eq!(bytes[..2], data[..2]);
+    assert_eq!(bytes[2..4], [0, 0]);
+    assert_eq!(bytes[4..12], data[4..12]);
+
+    let mut data = data;
+    data[13] = 0xAB;
+    assert!(matches!(
+        PaddedTextureHeader::try_from_bytes(data, Endianness::default()),
+        Err(TryFromBytesError::NonZeroPadding {
+            offset: 13,
+            byte: 0xAB
+        })
+    ));
+    Ok(())
+}
+
+#[derive(TryFromBytes, ToBytes, Debug, PartialEq, Clone)]
+struct MixedEndianRecord {
+    kind: u16,
+    #[pad(2)]
+    #[chomp(endian = "big")]
+    length: u32,
+    checksum: u32,
+}
+
+#[test]
+fn test_derive_field_endian() -> Result<(), Error> {
+    let data = [
+        0x01, 0x00, 0, 0, 0x00, 0x00, 0x01, 0x02, 0x04, 0x03, 0x02, 0x01,
+    ];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    let record = reader.read::<MixedEndianRecord>()?.inner();
+    assert_eq!(
+        record,
+        MixedEndianRecord {
+            kind: 1,
+            length: 0x0102,
+            checksum: 0x01020304
+        }
+    );
+    assert_eq!(record.to_bytes(Endianness::Little), data);
+
+    // the marked field stays big endian when the rest of the record is flipped
+    let mut writer = ByteWriter::new(Endianness::Big);
+    writer.append(record.clone());
+    let bytes = writer.buf();
+    assert_eq!(
+        bytes,
+        [0x00, 0x01, 0, 0, 0x00, 0x00, 0x01, 0x02, 0x01, 0x02, 0x03, 0x04]
+    );
+    let mut reader = ByteReader::new(&bytes, Endianness::Big);
+    assert_eq!(reader.read::<MixedEndianRecord>()?.inner(), record);
+    Ok(())
+}
+
+#[test]
+fn test_dotnet_7bit_encoded() -> Result<(), Error> {
+    // as written by System.IO.BinaryWriter
+    let mut writer = ByteWriter::new(Endianness::Little);
+    assert_eq!(writer.write_7bit_encoded_int(300), 2);
+    assert_eq!(writer.write_7bit_encoded_int(-1i32 as u32), 5);
+    assert_eq!(writer.append_dotnet_string("hello")?, 6);
+    assert_eq!(writer.append_dotnet_string("é")?, 3);
+    assert_eq!(writer.append_dotnet_string(&"a".repeat(200))?, 202);
+    let buf = writer.buf();
+    assert_eq!(
+        buf[..16],
+        [
+            0xAC, 0x02, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0x05, b'h', b'e', b'l', b'l', b'o', 0x02,
+            0xC3, 0xA9
+        ]
+    );
+    assert_eq!(buf[16..18], [0xC8, 0x01]);
+
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read_7bit_encoded_int()?, 300);
+    assert_eq!(reader.read_7bit_encoded_int()? as i32, -1);
+    assert_eq!(reader.read_dotnet_string()?, "hello");
+    assert_eq!(reader.read_dotnet_string()?, "é");
+    assert_eq!(reader.read_dotnet_string()?, "a".repeat(200));
+    assert!(reader.is_empty());
+
+    let buf = [0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_7bit_encoded_int(),
+        Err(Error {
+            kind: ErrorKind::VarintOverflow,
+            ..
+        })
+    ));
+    let buf = *b"\x06hello";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_dotnet_string(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 7,
+                available: 6,
+                ..
+            },
+            position: Some(0),
+            ..
+        })
+    ));
+
+    // invalid UTF-8 is reported at the bad byte, after the length
+    let buf = *b"\x01\x03ab\xFF";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.skip_bytes(1)?;
+    let err = reader.read_dotnet_string().unwrap_err();
+    assert!(matches!(
+        err,
+        Error {
+            kind: ErrorKind::InvalidUtf8 { offset: 4, ref error },
+            position: Some(1),
+            ..
+        } if error.as_bytes() == b"ab\xFF"
+    ));
+    assert_eq!(reader.cursor(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_read_terminated() -> Result<(), Error> {
+    let buf = [
+        0xFF, 0xFF, 0xFF, 0xFF, // an empty table
+        0x0A, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, // two entries
+        0x0C, 0x00, 0x00, 0x00, // a table without its sentinel
+    ];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read_terminated::<u32>(u32::MAX)?, vec![]);
+    assert_eq!(reader.cursor(), 4);
+    assert_eq!(reader.peek_terminated::<u32>(u32::MAX)?, vec![10, 11]);
+    assert_eq!(reader.cursor(), 4);
+    assert_eq!(
+        reader.read_terminated_with::<u32>(u32::MAX, false)?,
+        vec![10, 11]
+    );
+    assert_eq!(reader.cursor(), 12);
+    reader.seek(16)?;
+    assert!(matches!(
+        reader.read_terminated::<u32>(u32::MAX),
+        Err(Error {
+            kind: ErrorKind::NoSentinel { read: 1 },
+            position: Some(16),
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 16);
+    Ok(())
+}
+
+#[test]
+fn test_read_sized_vector_strings() -> Result<(), Error> {
+    let mut writer = ByteWriter::new(Endianness::Little);
+    writer.append::<u32>(3);
+    for name in ["albedo", "", "normal"] {
+        writer.append::<String>(name.to_string());
+    }
+    writer.append::<u8>(0xFF);
+    let buf = writer.buf();
+
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    let names = reader.read_sized_vector::<String>()?.flatten();
+    assert_eq!(names, vec!["albedo", "", "normal"]);
+    assert_eq!(reader.cursor(), 4 + 7 + 1 + 7);
+    assert_eq!(reader.read::<u8>()?.inner(), 0xFF);
+
+    // the last string is missing its terminator
+    let mut reader = ByteReader::new(&buf[..buf.len() - 2], Endianness::Little);
+    assert!(matches!(
+        reader.read_sized_vector::<String>().map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_seek_from() -> Result<(), Error> {
+    use std::io::SeekFrom;
+
+    let buf = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.seek_from(SeekFrom::Current(6))?, 6);
+    assert_eq!(reader.seek_from(SeekFrom::Current(-4))?, 2);
+    assert_eq!(reader.read::<u8>()?.inner(), 0x02);
+    assert_eq!(reader.seek_from(SeekFrom::End(-2))?, 6);
+    assert_eq!(reader.read::<u16>()?.inner(), 0x0706);
+
+    assert!(matches!(
+        reader.seek_from(SeekFrom::Current(-9)),
+        Err(Error {
+            kind: ErrorKind::SeekOutOfRange {
+                position: -1,
+                len: 8
+            },
+            position: Some(8),
+            ..
+        })
+    ));
+    assert!(matches!(
+        reader.seek_from(SeekFrom::End(1)),
+        Err(Error {
+            kind: ErrorKind::SeekOutOfRange {
+                position: 9,
+                len: 8
+            },
+            ..
+        })
+    ));
+    assert!(reader.seek_from(SeekFrom::Start(u64::MAX)).is_err());
+    assert!(reader.seek_from(SeekFrom::Current(i64::MAX)).is_err());
+    assert!(reader.seek(9).is_err());
+    assert_eq!(reader.cursor(), 8);
+
+    // the end of the buffer is a valid position with nothing left to read
+    reader.seek(0)?;
+    assert_eq!(reader.seek_from(SeekFrom::End(0))?, 8);
+    assert!(matches!(
+        reader.read::<u8>().map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(8),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_io_seek() -> Result<(), Error> {
+    use std::io::{BufRead, Seek, SeekFrom};
+
+    // reads a name from a table of offsets at the end of the data
+    fn parse<R: BufRead + Seek>(source: &mut R, index: i64) -> std::io::Result<Vec<u8>> {
+        source.seek(SeekFrom::End(-(index + 1)))?;
+        let offset = source.fill_buf()?[0];
+        source.consume(1);
+        let after = source.stream_position()?;
+        source.seek(SeekFrom::Start(offset as u64))?;
+        let mut name = Vec::new();
+        source.read_until(0x00, &mut name)?;
+        name.pop();
+        source.seek(SeekFrom::Start(after))?;
+        Ok(name)
+    }
+
+    let buf = *b"first\0second\0\x06\x00";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(parse(&mut reader, 0)?, b"first");
+    assert_eq!(reader.stream_position()?, buf.len() as u64);
+    assert_eq!(parse(&mut reader, 1)?, b"second");
+    assert_eq!(reader.stream_position()?, reader.cursor() as u64);
+    assert_eq!(reader.cursor(), buf.len() - 1);
+
+    let err = Seek::seek(&mut reader, SeekFrom::Current(-20)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(reader.cursor(), buf.len() - 1);
+    Ok(())
+}
+
+#[test]
+fn test_skip() -> Result<(), Error> {
+    let buf = *b"\x01\x02\x03\x04skipped\0kept\0\xAA\xBB";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.skip_bytes(1)?;
+    assert_eq!(reader.skip::<u8>()?, 1);
+    assert_eq!(reader.skip_n::<u8>(2)?, 2);
+    assert_eq!(reader.skip::<String>()?, 8);
+    assert_eq!(reader.cursor(), 12);
+    assert_eq!(reader.read_string()?, "kept");
+
+    assert!(matches!(
+        reader.skip_bytes(3),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { requested: 3, .. },
+            position: Some(17),
+            ..
+        })
+    ));
+    assert!(reader.skip::<u32>().is_err());
+    assert!(reader.skip::<String>().is_err());
+    assert!(reader.skip_n::<u8>(usize::MAX).is_err());
+    assert_eq!(reader.cursor(), 17);
+    assert_eq!(reader.skip::<u16>()?, 2);
+    assert!(reader.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_align() -> Result<(), Error> {
+    let buf = [0u8; 10];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.align(4)?, 0);
+    reader.skip_bytes(1)?;
+    assert_eq!(reader.align(4)?, 3);
+    assert_eq!(reader.align(4)?, 0);
+    assert_eq!(reader.cursor(), 4);
+
+    // a section starting at 5, aligned from its own start
+    reader.skip_bytes(2)?;
+    assert_eq!(reader.align_from(5, 4)?, 3);
+    assert_eq!(reader.cursor(), 9);
+
+    assert!(matches!(
+        reader.align(16),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(9),
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 9);
+
+    // impossible alignments are errors rather than panics or no-ops
+    assert!(matches!(
+        reader.align(0),
+        Err(Error {
+            kind: ErrorKind::InvalidAlignment {
+                origin: 0,
+                alignment: 0
+            },
+            position: Some(9),
+            ..
+        })
+    ));
+    assert!(matches!(
+        reader.align_from(10, 4),
+        Err(Error {
+            kind: ErrorKind::InvalidAlignment {
+                origin: 10,
+                alignment: 4
+            },
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 9);
+    assert_eq!(reader.align(2)?, 1);
+    assert!(reader.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_sub_reader() -> Result<(), Error> {
+    // a header, a 4 byte section and a trailer
+    let buf = [0xAA, 0x01, 0x00, 0x02, 0x00, 0xBB];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read::<u8>()?.inner(), 0xAA);
+    let mut section = reader.sub_reader(4)?;
+    assert_eq!(reader.cursor(), 5);
+
+    assert_eq!(section.read::<u16>()?.inner(), 1);
+    assert_eq!(section.read::<u8>()?.inner(), 2);
+    assert_eq!(section.cursor(), 3);
+    assert_eq!(section.absolute_cursor(), 4);
+    // the section ends before the trailer
+    assert!(matches!(
+        section.read::<u16>().map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(4),
+            ..
+        })
+    ));
+    assert_eq!(section.origin(), 1);
+
+    assert_eq!(reader.read::<u8>()?.inner(), 0xBB);
+    assert!(reader.sub_reader(1).is_err());
+    assert_eq!(reader.sub_reader(0)?.len(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_split_at() -> Result<(), Error> {
+    // a directory of (offset, length) pairs into the payload, then the payload
+    let buf = *b"\x02\x00\x03\x03\x02ZZabcdefg";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    let count = reader.read::<u8>()?.inner() as usize;
+    let (mut dir, payload) = reader.split_at(count * 2)?;
+    assert_eq!(reader.cursor(), 1);
+
+    let mut blobs = Vec::new();
+    for _ in 0..count {
+        let [offset, len] = dir.read::<[u8; 2]>()?.inner();
+        let (_, mut blob) = payload.split_at(offset as usize)?;
+        blobs.push(blob.read_string_fixed(len as usize)?);
+    }
+    assert_eq!(blobs, vec!["ZZa", "bc"]);
+    assert_eq!(payload.origin(), 5);
+
+    assert!(matches!(
+        reader.split_at(100),
+        Err(Error {
+            kind: ErrorKind::SplitOutOfRange {
+                offset: 100,
+                remaining: 13
+            },
+            position: Some(1),
+            ..
+        })
+    ));
+    let (head, tail) = reader.split_at(13)?;
+    assert_eq!((head.len(), tail.len()), (13, 0));
+    Ok(())
+}
+
+#[test]
+fn test_mark_reset() -> Result<(), Error> {
+    let buf = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    let start = reader.mark();
+    let first = (reader.read::<u8>()?.inner(), reader.read::<u16>()?.inner());
+    let middle = reader.mark();
+    let rest = reader.read::<[u8; 3]>()?.inner();
+
+    reader.reset(&start)?;
+    assert_eq!(
+        (reader.read::<u8>()?.inner(), reader.read::<u16>()?.inner()),
+        first
+    );
+    reader.reset(&middle)?;
+    assert_eq!(reader.read::<[u8; 3]>()?.inner(), rest);
+    reader.reset(&start)?;
+    assert_eq!(reader.cursor(), 0);
+
+    // marks after the new base stay valid, marks before it don't
+    reader.rebase(2)?;
+    reader.reset(&middle)?;
+    assert_eq!(reader.cursor(), 1);
+    assert_eq!(reader.read::<u8>()?.inner(), 0x04);
+    assert!(matches!(
+        reader.reset(&start),
+        Err(Error {
+            kind: ErrorKind::StaleMark {
+                position: 0,
+                origin: 2
+            },
+            position: Some(4),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_transaction() -> Result<(), Error> {
+    fn pascal(r: &mut ByteReader) -> Result<String, Error> {
+        let len = r.read::<u8>()?.inner() as usize;
+        r.read_string_fixed(len)
+    }
+
+    let buf = *b"\x05hello\x03ab";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    // the second string claims more bytes than remain
+    let res = reader.transaction(|r| {
+        let first = pascal(r)?;
+        let second = pascal(r)?;
+        Ok::<_, Error>((first, second))
+    });
+    assert!(matches!(
+        res,
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 0);
+
+    // only the inner transaction rolls back
+    let (name, rest) = reader.transaction(|r| {
+        let name = pascal(r)?;
+        let inner = r.transaction(pascal);
+        assert!(inner.is_err());
+        assert_eq!(r.cursor(), 6);
+        Ok::<_, Error>((name, r.read_remaining::<u8>()?.len()))
+    })?;
+    assert_eq!((name.as_str(), rest), ("hello", 3));
+    assert!(reader.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_position_stack() -> Result<(), Error> {
+    let buf = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.read::<u8>()?;
+
+    let values = reader.with_pos(5, |r| {
+        let a = r.read::<u8>()?.inner();
+        let (b, c) = r.with_pos(3, |r| {
+            let b = r.read::<u8>()?.inner();
+            let c = r.with_pos(0, |r| r.read::<u8>().map(|c| c.inner()))?;
+            assert_eq!(r.cursor(), 4);
+            Ok::<_, Error>((b, c))
+        })?;
+        assert_eq!(r.cursor(), 6);
+        Ok::<_, Error>([a, b, c])
+    })?;
+    assert_eq!(values, [0x66, 0x44, 0x11]);
+    assert_eq!(reader.cursor(), 1);
+
+    // failed closures and seeks still restore the cursor
+    assert!(reader.with_pos(4, |r| r.read::<u32>()).is_err());
+    assert!(reader.with_pos(10, |r| r.read::<u8>()).is_err());
+    assert_eq!(reader.cursor(), 1);
+
+    // a push inside the closure without a pop doesn't change where it returns to
+    reader.with_pos(3, |r| {
+        r.push_pos();
+        r.skip_bytes(1)
+    })?;
+    assert_eq!(reader.cursor(), 1);
+    reader.pop_pos()?;
+    assert_eq!(reader.cursor(), 3);
+    reader.seek(1)?;
+
+    reader.push_pos();
+    reader.seek(4)?;
+    reader.push_pos();
+    reader.seek(0)?;
+    reader.pop_pos()?;
+    assert_eq!(reader.cursor(), 4);
+    reader.pop_pos()?;
+    assert_eq!(reader.cursor(), 1);
+    assert!(matches!(
+        reader.pop_pos(),
+        Err(Error {
+            kind: ErrorKind::EmptyPositionStack,
+            position: Some(1),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_read_at() -> Result<(), Error> {
+    // a header of offsets into a table of u16s, read in between the header fields
+    let buf = [
+        0x06, 0x08, 0x0A, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00,
+    ];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    let mut values = Vec::new();
+    for _ in 0..3 {
+        let offset = reader.read::<u8>()?.inner() as usize;
+        values.push(reader.read_at::<u16>(offset)?.inner());
+    }
+    assert_eq!(values, [1, 2, 3]);
+    assert_eq!(reader.cursor(), 3);
+    assert_eq!(reader.read_n_at::<u16>(6, 3)?.flatten(), [1, 2, 3]);
+    assert_eq!(reader.read::<[u8; 3]>()?.inner(), [0, 0, 0]);
+
+    assert!(matches!(
+        reader.read_at::<u8>(13).map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::SeekOutOfRange {
+                position: 13,
+                len: 12
+            },
+            position: Some(6),
+            ..
+        })
+    ));
+    // in range, but not enough bytes for the value
+    assert!(matches!(
+        reader.read_at::<u16>(11).map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(11),
+            ..
+        })
+    ));
+
+    // positions are relative to the new base after a rebase
+    reader.rebase(6)?;
+    assert_eq!(reader.read_at::<u16>(2)?.inner(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_peek_at() -> Result<(), Error> {
+    let buf = *b"\x04\x00\xAA\xBBDATA\x01\x02";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    assert_eq!(reader.peek_at::<[u8; 4]>(4)?.inner(), *b"DATA");
+    assert_eq!(reader.peek_bytes_at(2, 2)?, [0xAA, 0xBB]);
+    assert_eq!(reader.peek_at::<u16>(8)?.inner(), 0x0201);
+    assert_eq!(reader.peek_bytes_at(10, 0)?, []);
+    assert!(matches!(
+        reader.peek_at::<u32>(8).map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::PastEnd { overrun: 2 },
+            position: Some(0),
+            ..
+        })
+    ));
+    assert!(matches!(
+        reader.peek_bytes_at(usize::MAX, 1),
+        Err(Error {
+            kind: ErrorKind::PastEnd { .. },
+            position: Some(0),
+            ..
+        })
+    ));
+
+    // nothing was consumed by the lookahead
+    assert_eq!(reader.read_n::<u16>(2)?.flatten(), [0x0004, 0xBBAA]);
+    assert!(matches!(
+        reader.peek_bytes_at(4, 3),
+        Err(Error {
+            kind: ErrorKind::PastEnd { overrun: 1 },
+            position: Some(4),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_find() -> Result<(), Error> {
+    let buf = *b"SYNC\x01\x02garbageSYN\x03\x04SYNC";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    // at the cursor
+    assert_eq!(reader.find(b"SYNC"), Some(0));
+    assert_eq!(reader.seek_to_pattern(b"SYNC")?, 0);
+    reader.skip_bytes(4)?;
+    assert_eq!(reader.read::<u16>()?.inner(), 0x0201);
+
+    // a partial match is skipped, and the needle ends the buffer
+    assert_eq!(reader.find(b"SYNC"), Some(12));
+    assert_eq!(reader.seek_to_pattern(b"SYNC")?, 12);
+    assert_eq!(reader.cursor(), 18);
+    assert_eq!(reader.len(), 4);
+
+    // absent
+    reader.skip_bytes(1)?;
+    assert_eq!(reader.find(b"SYNC"), None);
+    assert!(matches!(
+        reader.seek_to_pattern(b"SYNC"),
+        Err(Error {
+            kind: ErrorKind::NoPattern,
+            position: Some(19),
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 19);
+
+    // across a large buffer
+    let mut big = vec![0xAA; 1 << 22];
+    big.extend_from_slice(b"\x7FELF");
+    let reader = ByteReader::new(&big, Endianness::Little);
+    assert_eq!(reader.find(b"\x7FELF"), Some(1 << 22));
+    Ok(())
+}
+
+#[test]
+fn test_expect_magic() -> Result<(), Error> {
+    let buf = *b"RIFF\x04\x03\x02\x01WA";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    assert!(matches!(
+        reader.expect_magic(b"RIFX"),
+        Err(Error {
+            kind: ErrorKind::BadMagic { expected, actual },
+            position: Some(0),
+            ..
+        }) if expected == b"RIFX" && actual == b"RIFF"
+    ));
+    reader.expect_magic(b"RIFF")?;
+    assert_eq!(reader.cursor(), 4);
+
+    // integer magics follow the reader's endianness
+    assert!(reader.expect_magic_u32(0x04030201).is_err());
+    assert_eq!(reader.cursor(), 4);
+    reader.expect_magic_u32(0x01020304)?;
+
+    assert!(matches!(
+        reader.expect_magic(b"WAVE"),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 4,
+                available: 2,
+                ..
+            },
+            position: Some(8),
+            ..
+        })
+    ));
+    assert_eq!(reader.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_rebase() -> Result<(), Error> {
+    let buf = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    assert!(matches!(
+        reader.rebase(9),
+        Err(Error {
+            kind: ErrorKind::SeekOutOfRange {
+                position: 9,
+                len: 8
+            },
+            position: Some(0),
+            ..
+        })
+    ));
+
+    reader.rebase(2)?;
+    reader.skip_bytes(1)?;
+    reader.rebase_to_cursor();
+    assert_eq!(reader.origin(), 3);
+    assert_eq!(reader.read::<u8>()?.inner(), 0x03);
+    assert_eq!((reader.cursor(), reader.absolute_cursor()), (1, 4));
+
+    // seek is relative to the new base, errors to the original buffer
+    reader.seek(0)?;
+    assert_eq!(reader.read::<u8>()?.inner(), 0x03);
+    reader.seek(4)?;
+    assert!(matches!(
+        reader.read::<u16>().map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(7),
+            ..
+        })
+    ));
+    reader.rebase(5)?;
+    assert!(reader.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_remaining() -> Result<(), Error> {
+    let buf = *b"\x02\x00payload";
+    let tail = {
+        let mut reader = ByteReader::new(&buf, Endianness::Little);
+        reader.read::<u16>()?;
+        assert_eq!(reader.remaining(), b"payload");
+        assert_eq!(reader.cursor(), 2);
+        let tail = reader.take_remaining();
+        assert_eq!(reader.len(), 0);
+        assert!(reader.remaining().is_empty());
+        tail
+    };
+    // the slice borrows the buffer rather than the reader
+    assert_eq!(tail, b"payload");
+    Ok(())
+}
+
+#[test]
+fn test_consume() -> Result<(), Error> {
+    use std::io::BufRead;
+
+    let buf = [1, 2, 3, 4, 5];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.consume(2);
+    assert!(matches!(
+        reader.try_consume(4),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 4,
+                available: 3,
+                ..
+            },
+            position: Some(2),
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 2);
+    reader.try_consume(3)?;
+    assert!(reader.is_empty());
+
+    // consuming past the end stops at the end rather than panicking
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.consume(usize::MAX);
+    assert_eq!(reader.cursor(), buf.len());
+    assert!(reader.remaining().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_io_read() -> Result<(), Error> {
+    use std::io::Read;
+
+    let buf = *b"\x02\x00payload";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.read::<u16>()?;
+    let expected = reader.remaining().to_vec();
+    let mut copied = Vec::new();
+    assert_eq!(std::io::copy(&mut reader, &mut copied)?, 7);
+    assert_eq!(copied, expected);
+    assert_eq!(reader.cursor(), buf.len());
+    assert_eq!(Read::read(&mut reader, &mut [0; 4])?, 0);
+
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.seek(2)?;
+    let mut head = [0; 3];
+    reader.read_exact(&mut head)?;
+    assert_eq!(&head, b"pay");
+    assert_eq!(reader.remaining(), b"load");
+    assert!(reader.read_exact(&mut [0; 5]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_context() -> Result<(), Error> {
+    let buf = *b"RIFF\x24\x00\x00\x00WAVEfmt \x10\x00";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.seek(10)?;
+    assert_eq!(reader.consumed(), b"RIFF\x24\x00\x00\x00WA");
+
+    let marker = format!("{}^^\n", " ".repeat(40));
+    assert_eq!(
+        reader.context(4, 4),
+        format!(
+            "00000000                    00 00 57 41 56 45 66 6d        |      ..WAVEfm  |\n{marker}"
+        )
+    );
+    // the window is cut off at both ends of the buffer
+    assert_eq!(
+        reader.context(16, 16),
+        format!(
+            "00000000  52 49 46 46 24 00 00 00 57 41 56 45 66 6d 74 20  |RIFF$...WAVEfmt |\n\
+             {marker}\
+             00000010  10 00                                            |..              |\n"
+        )
+    );
+
+    // offsets are in the original buffer, and the cursor can be at the end
+    reader.rebase(16)?;
+    reader.skip_bytes(2)?;
+    let err = reader.read::<u8>().map(|_| ()).unwrap_err();
+    assert_eq!(
+        err.context(&reader),
+        format!(
+            "00000010  10 00                                            |..              |\n{}^^\n",
+            " ".repeat(16)
+        )
+    );
+    assert_eq!(err.context(&reader), reader.context(16, 16));
+    Ok(())
+}
+
+#[test]
+fn test_read_exact_bytes() -> Result<(), Error> {
+    let buf = [0x01, 0x02, 0x03, 0x04, 0x05];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    let mut out = [0; 3];
+    reader.read_exact_bytes(&mut out)?;
+    assert_eq!(out, [0x01, 0x02, 0x03]);
+
+    assert!(matches!(
+        reader.read_exact_bytes(&mut out),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 3,
+                available: 2,
+                ..
+            },
+            position: Some(3),
+            ..
+        })
+    ));
+    assert_eq!(out, [0x01, 0x02, 0x03]);
+    assert_eq!(reader.cursor(), 3);
+
+    reader.read_exact_bytes(&mut [])?;
+    reader.read_exact_bytes(&mut out[..2])?;
+    assert_eq!(out, [0x04, 0x05, 0x03]);
+    assert!(reader.is_empty());
+    reader.read_exact_bytes(&mut [])?;
+    Ok(())
+}
+
+#[test]
+fn test_read_slice() -> Result<(), Error> {
+    let buf = *b"\x04blob\x02";
+    let blob = {
+        let mut reader = ByteReader::new(&buf, Endianness::Little);
+        let len = reader.read::<u8>()?.inner() as usize;
+        assert_eq!(reader.peek_slice(len)?, b"blob");
+        let blob = reader.read_slice(len)?;
+        assert_eq!(reader.cursor(), 5);
+
+        assert!(matches!(
+            reader.read_slice(2),
+            Err(Error {
+                kind: ErrorKind::UnexpectedEof {
+                    requested: 2,
+                    available: 1,
+                    type_name: "[u8]"
+                },
+                position: Some(5),
+                ..
+            })
+        ));
+        assert_eq!(reader.read::<u8>()?.inner(), 2);
+        blob
+    };
+    // the slice points into the original buffer
+    assert_eq!(blob, b"blob");
+    assert_eq!(blob.as_ptr(), buf[1..].as_ptr());
+    Ok(())
+}
+
+#[test]
+fn test_read_n_into() -> Result<(), Error> {
+    let table: Vec<u32> = (0..1 << 16).map(|i| i * 3).collect();
+    let mut writer = ByteWriter::new(Endianness::Big);
+    for &value in &table {
+        writer.append(value);
+    }
+    let buf = writer.buf();
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+
+    // the same buffers are reused for every pair of rows
+    let mut row = [0u32; 256];
+    let mut rows: Vec<u32> = Vec::with_capacity(256);
+    for pair in table.chunks(512) {
+        reader.read_n_into(&mut row)?;
+        assert_eq!(row, pair[..256]);
+        reader.read_n_into_vec(&mut rows, 256)?;
+        assert_eq!(rows, pair[256..]);
+        assert_eq!(rows.capacity(), 256);
+    }
+    assert!(reader.is_empty());
+
+    // nothing is consumed on failure
+    let buf = *b"ab\0cd";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(reader.read_n_into(&mut [0u16; 3]).is_err());
+    let mut strings = Vec::new();
+    assert!(reader.read_n_into_vec::<String>(&mut strings, 2).is_err());
+    assert_eq!(reader.cursor(), 0);
+    reader.read_n_into_vec(&mut strings, 1)?;
+    assert_eq!(strings, ["ab"]);
+    assert!(matches!(
+        reader.read_n_into(&mut [0u16; 2]),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 4,
+                available: 2,
+                ..
+            },
+            position: Some(3),
+            ..
+        })
+    ));
+
+    // zero sized values take no bytes, even from an empty buffer
+    let mut reader = ByteReader::new(&[], Endianness::Little);
+    let mut empty = [[0u8; 0]; 3];
+    reader.read_n_into(&mut empty)?;
+    let mut empties = Vec::new();
+    reader.read_n_into_vec::<[u8; 0]>(&mut empties, 2)?;
+    assert_eq!(empties.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_iter_results() -> Result<(), Error> {
+    // three whole u32s and a partial one
+    let buf = [1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    let mut values = reader.iter_results::<u32>();
+    assert_eq!(values.next().transpose()?, Some(1));
+    assert_eq!(values.next().transpose()?, Some(2));
+    assert_eq!(values.next().transpose()?, Some(3));
+    assert!(matches!(
+        values.next(),
+        Some(Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(12),
+            ..
+        }))
+    ));
+    assert!(values.next().is_none());
+    assert_eq!(reader.len(), 2);
+
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(reader
+        .iter_results::<u32>()
+        .collect::<Result<Vec<_>, _>>()
+        .is_err());
+    let mut reader = ByteReader::new(&buf[..12], Endianness::Little);
+    assert_eq!(
+        reader
+            .iter_results::<u32>()
+            .collect::<Result<Vec<_>, _>>()?,
+        [1, 2, 3]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_chunks() -> Result<(), Error> {
+    // position and normal triples of vertices
+    let buf: Vec<u8> = (0..24).flat_map(|i: u16| i.to_be_bytes()).collect();
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    let vertices = reader.chunks::<u16>(6).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(vertices.len(), 4);
+    assert_eq!(vertices[1], [6, 7, 8, 9, 10, 11]);
+    assert!(reader.is_empty());
+
+    // a partial chunk at the end
+    let mut reader = ByteReader::new(&buf[..20], Endianness::Big);
+    let mut chunks = reader.chunks::<u16>(4);
+    assert_eq!(chunks.next().transpose()?, Some(vec![0, 1, 2, 3]));
+    assert_eq!(chunks.next().transpose()?, Some(vec![4, 5, 6, 7]));
+    assert!(matches!(
+        chunks.next(),
+        Some(Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(16),
+            ..
+        }))
+    ));
+    assert!(chunks.next().is_none());
+    assert_eq!(reader.cursor(), 16);
+
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    let mut triples = reader.chunks_const::<u16, 3>();
+    assert_eq!(triples.next().transpose()?, Some([0, 1, 2]));
+    assert_eq!(triples.last().transpose()?, Some([21, 22, 23]));
+    assert_eq!(reader.chunks::<u8>(0).count(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_iter_then_read() -> Result<(), Error> {
+    let buf = [0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04, 0x00, 0x05, 0x00];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    let mut sum = 0;
+    for value in reader.iter::<u16>().take(2) {
+        sum += value;
+    }
+    assert_eq!(sum, 3);
+    assert_eq!(reader.cursor(), 4);
+
+    // reading and iterating can be interleaved
+    assert_eq!(reader.read::<u16>()?.inner(), 3);
+    let rest: Vec<u16> = reader.iter().collect();
+    assert_eq!(rest, [4, 5]);
+    assert!(reader.is_empty());
+    assert!(reader.read::<u8>().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_streaming_reader() -> Result<(), Error> {
+    let mut writer = ByteWriter::new(Endianness::Big);
+    writer.append(0xAAu8);
+    writer.append(0x01020304u32);
+    writer.append(String::from("a string longer than a chunk"));
+    writer.append(0x0506u16);
+    writer.append(0x0708090A0B0C0D0Eu64);
+    writer.append(String::from("name"));
+    writer.append(0xFFu8);
+    let buf = writer.buf();
+
+    // a tiny chunk size so values straddle refills
+    let mut reader =
+        StreamingByteReader::new(std::io::Cursor::new(&buf), Endianness::Big).with_chunk_size(3);
+    assert_eq!(reader.read::<u8>()?, 0xAA);
+    assert_eq!(reader.read::<u32>()?, 0x01020304);
+    assert_eq!(reader.read::<String>()?, "a string longer than a chunk");
+    assert_eq!(reader.read_n::<u8>(2)?, [0x05, 0x06]);
+    reader.skip_bytes(4)?;
+    assert_eq!(reader.read::<u32>()?, 0x0B0C0D0E);
+    assert_eq!(reader.read_string()?, "name");
+    assert_eq!(reader.position(), buf.len() - 1);
+
+    // the stream ends partway through a value
+    assert!(matches!(
+        reader.read::<u16>(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(position),
+            ..
+        }) if position == buf.len() - 1
+    ));
+    assert_eq!(reader.read::<u8>()?, 0xFF);
+    assert!(reader.read_string().is_err());
+    assert!(matches!(
+        reader.skip_bytes(2),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 2,
+                available: 0,
+                ..
+            },
+            position: Some(position),
+            ..
+        }) if position == buf.len()
+    ));
+
+    // matches the in memory reader
+    let mut streamed =
+        StreamingByteReader::new(std::io::Cursor::new(&buf), Endianness::Big).with_chunk_size(8);
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    let n = buf.len() / 2;
+    assert_eq!(
+        streamed.read_n::<u16>(n)?,
+        reader.read_n::<u16>(n)?.flatten()
+    );
+    Ok(())
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap() -> Result<(), Error> {
+    let data = std::fs::read("test/texture.text")?;
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    // SAFETY: nothing writes to the test files
+    let file = unsafe { ByteReader::from_mmap("test/texture.text", Endianness::Little)? };
+    let mut mapped = file.reader();
+
+    assert_eq!(mapped.len(), data.len());
+    let n = data.len() / 4;
+    assert_eq!(
+        mapped.read_n::<u32>(n)?.flatten(),
+        reader.read_n::<u32>(n)?.flatten()
+    );
+    assert_eq!(mapped.remaining(), reader.remaining());
+
+    assert!(matches!(
+        unsafe { ByteReader::from_mmap("test/missing.file", Endianness::Little) },
+        Err(Error {
+            kind: ErrorKind::Io(_),
+            position: None,
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_owned_reader() -> Result<(), Error> {
+    fn load() -> ByteReaderOwned {
+        let buf: Vec<u8> = (0..1024u32).flat_map(|i| i.to_le_bytes()).collect();
+        ByteReaderOwned::new(buf, Endianness::Little)
+    }
+
+    let mut reader = load();
+    assert_eq!(reader.read::<u32>()?, 0);
+    assert_eq!(reader.peek_n::<u32>(2)?, [1, 2]);
+    assert_eq!(reader.cursor(), 4);
+
+    // clones share the buffer but not the cursor
+    let threads: Vec<_> = (0..2)
+        .map(|i| {
+            let mut reader = reader.clone();
+            std::thread::spawn(move || {
+                reader.seek(i * 2048).unwrap();
+                reader.read_n::<u32>(512).unwrap()
+            })
+        })
+        .collect();
+    let halves: Vec<Vec<u32>> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+    assert_eq!(halves[0], (0..512).collect::<Vec<_>>());
+    assert_eq!(halves[1], (512..1024).collect::<Vec<_>>());
+    assert_eq!(reader.read::<u32>()?, 1);
+
+    let buf = *b"\x00\x00name\x00\x2A";
+    let mut borrowed = ByteReader::new(&buf, Endianness::Little);
+    borrowed.rebase(2)?;
+    let mut owned = borrowed.to_owned();
+    drop(borrowed);
+    assert_eq!(owned.read_string()?, "name");
+    assert!(matches!(
+        owned.read::<u16>(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(7),
+            ..
+        })
+    ));
+    assert_eq!(owned.read::<u8>()?, 0x2A);
+    Ok(())
+}
+
+#[test]
+fn test_chained_reader() -> Result<(), Error> {
+    let header = *b"HDR\x01";
+    let body = *b"\x02\x03\x04a";
+    let empty = [];
+    let trailer = *b"b\0\x05\x06\x07";
+    let mut reader = ByteReader::chain(&[&header, &body, &empty, &trailer], Endianness::Little);
+    assert_eq!(reader.len(), 13);
+
+    reader.skip_bytes(3)?;
+    // a u32 across the first two segments
+    assert_eq!(reader.read::<u32>()?, 0x04030201);
+    assert_eq!(reader.cursor(), 7);
+    // and a string across the last two
+    assert_eq!(reader.read::<String>()?, "ab");
+
+    // seek into the last segment, then back across the boundary
+    reader.seek(10)?;
+    assert_eq!(reader.peek::<u8>()?, 0x05);
+    reader.seek(6)?;
+    assert_eq!(reader.read_n::<u16>(2)?, [0x6104, 0x0062]);
+    assert_eq!(reader.segment_of(reader.cursor()), 3);
+
+    assert!(matches!(
+        reader.read::<u32>(),
+        Err(Error {
+            kind: ErrorKind::InSegment { segment: 3, error },
+            position: Some(10),
+            ..
+        }) if matches!(
+            *error,
+            ErrorKind::UnexpectedEof {
+                requested: 4,
+                available: 3,
+                type_name: "u32"
+            }
+        )
+    ));
+    assert!(reader.skip_bytes(4).unwrap_err().is_eof());
+    assert!(reader.seek(14).is_err());
+    assert_eq!(reader.cursor(), 10);
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_reader() -> Result<(), Error> {
+    use tokio::io::AsyncWriteExt;
+
+    use crate::AsyncByteReader;
+
+    let mut writer = ByteWriter::new(Endianness::Little);
+    writer.append(0x01020304u32);
+    writer.append(String::from("hello"));
+    writer.append(3u32);
+    for value in [10u16, 20, 30] {
+        writer.append(value);
+    }
+    writer.append(0xAAu8);
+    writer.append(0x0102u16);
+    let buf = writer.buf();
+
+    // feed the reader a byte at a time
+    let (mut tx, rx) = tokio::io::duplex(1);
+    let feed = tokio::spawn(async move {
+        for byte in buf {
+            tx.write_all(&[byte]).await.unwrap();
+        }
+        // the last u16 is cut short
+        tx.write_all(&[0x05]).await.unwrap();
+    });
+
+    let mut reader = AsyncByteReader::new(rx, Endianness::Little);
+    assert_eq!(reader.read::<u32>().await?, 0x01020304);
+    assert_eq!(reader.read_string().await?, "hello");
+    assert_eq!(reader.read_sized_vector::<u16>().await?, [10, 20, 30]);
+    reader.skip_bytes(1).await?;
+    assert_eq!(reader.read_n::<u8>(2).await?, [0x02, 0x01]);
+    assert_eq!(reader.position(), 23);
+    assert!(matches!(
+        reader.read::<u16>().await,
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            position: Some(23),
+            ..
+        })
+    ));
+    feed.await.unwrap();
+    assert_eq!(reader.read::<u8>().await?, 0x05);
+    assert!(matches!(
+        reader.skip_bytes(2).await,
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 2,
+                available: 0,
+                ..
+            },
+            position: Some(24),
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_set_endianness() -> Result<(), Error> {
+    // a TIFF style header, with the byte order for the rest of the file up front
+    fn write_header(endianness: Endianness) -> Vec<u8> {
+        let mut writer = ByteWriter::new(Endianness::Little);
+        writer.append_vec(match endianness {
+            Endianness::Little => b"II".to_vec(),
+            Endianness::Big => b"MM".to_vec(),
+        });
+        writer.set_endianness(endianness);
+        assert!(writer.endianness() == endianness);
+        writer.append(42u16);
+        writer.append(0x0000_0108u32);
+        writer.buf()
+    }
+
+    fn read_header(buf: &[u8]) -> Result<(u16, u32), Error> {
+        let mut reader = ByteReader::new(buf, Endianness::Little);
+        match &reader.read::<[u8; 2]>()?.inner() {
+            b"MM" => reader.set_endianness(Endianness::Big),
+            _ => reader.set_endianness(Endianness::Little),
+        }
+        Ok((reader.read::<u16>()?.inner(), reader.read::<u32>()?.inner()))
+    }
+
+    let little = write_header(Endianness::Little);
+    let big = write_header(Endianness::Big);
+    assert_eq!(big, b"MM\x00\x2A\x00\x00\x01\x08");
+    assert_eq!(read_header(&little)?, (42, 0x108));
+    assert_eq!(read_header(&big)?, (42, 0x108));
+
+    // a chomp keeps the endianness it was read with
+    let mut reader = ByteReader::new(&big[2..], Endianness::Big);
+    let magic = reader.read::<u16>()?;
+    reader.set_endianness(Endianness::Little);
+    assert!(reader.endianness() == Endianness::Little);
+    assert_eq!(magic.inner(), 42);
+    assert_eq!(reader.read::<u32>()?.inner(), 0x0801_0000);
+    Ok(())
+}
+
+#[test]
+fn test_read_as() -> Result<(), Error> {
+    // little endian fields with a network order port and addresses in between
+    let buf = [
+        0x78, 0x56, 0x34, 0x12, 0x1F, 0x90, 0x0A, 0x00, 0x00, 0x01, 0xEF, 0xBE, 0xAD, 0xDE,
+    ];
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+
+    assert_eq!(reader.read_le::<u32>()?.inner(), 0x12345678);
+    assert_eq!(reader.peek_be::<u16>()?.inner(), 8080);
+    assert_eq!(reader.peek_le::<u16>()?.inner(), 0x901F);
+    assert_eq!(reader.read_be::<u16>()?.inner(), 8080);
+    assert_eq!(reader.read_n_be::<u16>(2)?.flatten(), [0x0A00, 0x0001]);
+    assert!(reader.endianness() == Endianness::Big);
+    reader.seek(6)?;
+    assert_eq!(
+        reader.read_n_le::<u8>(4)?.flatten(),
+        [0x0A, 0x00, 0x00, 0x01]
+    );
+    assert_eq!(
+        reader.read_as::<u32>(Endianness::Little)?.inner(),
+        0xDEADBEEF
+    );
+
+    let mut writer = ByteWriter::new(Endianness::Big);
+    writer.append_le(0x12345678u32);
+    writer.append_be(8080u16);
+    writer.append(0x0A000001u32);
+    writer.append_as(0xDEADBEEFu32, Endianness::Little);
+    assert_eq!(writer.buf(), buf);
+    Ok(())
+}
+
+/// An RGB colour stored in 3 bytes, though padded to 4 in memory
+#[derive(Clone, Debug, PartialEq)]
+#[repr(C, align(4))]
+struct Rgb {
+    r: u8,
+    g: u8,
+    b: u8,
+}
+
+impl TryFromBytes for Rgb {
+    type Bytes = Vec<u8>;
+
+    const SIZE: usize = 3;
+
+    fn try_from_bytes(bytes: Self::Bytes, _: Endianness) -> Result<(Self, usize), Self::Error> {
+        match bytes[..] {
+            [r, g, b, ..] => Ok((Rgb { r, g, b }, 3)),
+            _ => Err(TryFromBytesError::OutOfBounds),
+        }
+    }
+}
+
+/// An unsigned LEB128 length, taking as many bytes as it needs
+#[derive(Clone, Debug, PartialEq)]
+struct VarLen(usize);
+
+impl TryFromBytes for VarLen {
+    type Bytes = Vec<u8>;
+
+    const VARIABLE_SIZE: bool = true;
+
+    fn try_from_bytes(bytes: Self::Bytes, _: Endianness) -> Result<(Self, usize), Self::Error> {
+        let mut value = 0;
+        for (i, byte) in bytes.iter().take(4).enumerate() {
+            value |= ((byte & 0x7F) as usize) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok((VarLen(value), i + 1));
+            }
+        }
+        Err(TryFromBytesError::OutOfBounds)
+    }
+}
+
+impl From<VarLen> for usize {
+    fn from(len: VarLen) -> usize {
+        len.0
+    }
+}
+
+#[test]
+fn test_read_prefixed_variable_size() -> Result<(), Error> {
+    // a two byte LEB128 length of 130 followed by the block and a trailing byte
+    let mut buf = vec![0x82, 0x01];
+    buf.extend(0..130);
+    buf.push(0xFF);
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    let block = reader
+        .read_prefixed_strict::<VarLen, _>(|block| Ok(block.read_slice(block.len())?.to_vec()))?;
+    assert_eq!(block, (0..130).collect::<Vec<u8>>());
+    assert_eq!(reader.read::<u8>()?.inner(), 0xFF);
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Record {
+    version: u8,
+    value: u8,
+}
+
+#[derive(Debug, PartialEq)]
+enum RecordError {
+    UnsupportedVersion(u8),
+    ChecksumMismatch { expected: u8, actual: u8 },
+}
+
+impl std::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordError::UnsupportedVersion(version) => write!(f, "unsupported version {version}"),
+            RecordError::ChecksumMismatch { .. } => write!(f, "checksum mismatch in record"),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl TryFromBytes for Record {
+    type Bytes = Vec<u8>;
+    type Error = RecordError;
+
+    const SIZE: usize = 3;
+
+    fn try_from_bytes(bytes: Self::Bytes, _: Endianness) -> Result<(Self, usize), Self::Error> {
+        let [version, value, checksum] = bytes[..3] else {
+            unreachable!("readers pass at least SIZE bytes")
+        };
+        if version != 1 {
+            return Err(RecordError::UnsupportedVersion(version));
+        }
+        if checksum != version ^ value {
+            return Err(RecordError::ChecksumMismatch {
+                expected: version ^ value,
+                actual: checksum,
+            });
+        }
+        Ok((Record { version, value }, 3))
+    }
+}
+
+#[test]
+fn test_custom_error() -> Result<(), Error> {
+    use std::error::Error as _;
+
+    let buf = [1, 0x10, 0x11, 1, 0x20, 0x00, 7, 0, 0];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read_n::<Record>(1)?.flatten()[0].value, 0x10);
+
+    let err = reader.read_value::<Record>().unwrap_err();
+    assert!(matches!(
+        err,
+        Error {
+            kind: ErrorKind::Decode(TryFromBytesError::Custom(_)),
+            position: Some(3),
+            ..
+        }
+    ));
+    assert_eq!(err.code(), ErrorCode::Custom);
+    let custom = err.source().unwrap().source().unwrap();
+    assert_eq!(
+        custom.downcast_ref::<RecordError>(),
+        Some(&RecordError::ChecksumMismatch {
+            expected: 0x21,
+            actual: 0
+        })
+    );
+    assert_eq!(reader.cursor(), 3);
+
+    reader.seek(6)?;
+    let err = reader.read::<Record>().unwrap_err();
+    let custom = err.source().unwrap().source().unwrap();
+    assert_eq!(custom.to_string(), "unsupported version 7");
+
+    // errors which are already TryFromBytesErrors aren't wrapped
+    assert!(matches!(
+        TryFromBytesError::custom(TryFromBytesError::ZeroValue),
+        TryFromBytesError::ZeroValue
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_read_variable_size() -> Result<(), Error> {
+    let buf = *b"first\0second\0\x2A";
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read::<String>()?.inner(), "first");
+    assert_eq!(reader.read::<String>()?.inner(), "second");
+    assert_eq!(reader.read::<u8>()?.inner(), 42);
+
+    // the encoded size is used rather than the size in memory
+    assert_eq!(size_of::<Rgb>(), 4);
+    let buf = [1, 2, 3, 4, 5, 6, 7, 8, 9, 0xFF];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    let colours: Vec<Rgb> = reader.read_n::<Rgb>(2)?.flatten();
+    assert_eq!(
+        colours,
+        [Rgb { r: 1, g: 2, b: 3 }, Rgb { r: 4, g: 5, b: 6 }]
+    );
+    assert_eq!(reader.cursor(), 6);
+    assert_eq!(reader.read::<Rgb>()?.inner(), Rgb { r: 7, g: 8, b: 9 });
+    assert_eq!(reader.read::<u8>()?.inner(), 0xFF);
+    Ok(())
+}
+
+#[test]
+fn test_read_misaligned() -> Result<(), Error> {
+    // the u32s start at an odd address, so they can't be read through a &[u32]
+    let buf = [0xFF, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.read::<u8>()?;
+    assert_eq!(reader.peek_n::<u32>(3)?.flatten(), [1, 2, 3]);
+    assert_eq!(reader.read_n::<u32>(3)?.flatten(), [1, 2, 3]);
+
+    let mut reader = ByteReader::new(&buf[1..], Endianness::Big);
+    assert_eq!(reader.read::<u32>()?.inner(), 0x01000000);
+    Ok(())
+}
+
+#[test]
+fn test_chomp_outlives_reader() -> Result<(), Error> {
+    let buf = *b"\x2A\x00name\0";
+    // chomps borrow the buffer rather than the reader
+    let (value, name) = {
+        let mut reader = ByteReader::new(&buf, Endianness::Little);
+        (reader.read::<u16>()?, reader.read::<String>()?)
+    };
+    assert_eq!(value.inner(), 42);
+    assert_eq!(value.bytes(), b"\x2A\x00");
+    assert_eq!(name.inner(), "name");
+    assert_eq!(name.size(), 5);
+    Ok(())
+}
+
+#[test]
+fn test_chomp_traits() -> Result<(), Error> {
+    let buf = [0x03, 0x00, 0x01, 0x00, 0x02, 0x00, 0x01, 0x00];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    let mut values = reader.read_n::<u16>(4)?;
+    assert_eq!(values[1], values[3]);
+    assert_ne!(values[0], values[1]);
+    assert!(values[0] > values[2]);
+    assert!(values[0] > 2);
+
+    values.sort();
+    assert_eq!(values, [1, 1, 2, 3]);
+    assert_eq!(format!("{:?}", values[3]), "3");
+    assert_eq!(format!("{:04}", values[3]), "0003");
+    Ok(())
+}
+
+#[test]
+fn test_chomp_flatten() -> Result<(), Error> {
+    let data = std::fs::read("test/texture.text")?;
+    let mut reader = ByteReader::new(&data, Endianness::default());
+    reader.seek(0x1c)?;
+    let offsets = reader.read_n::<u32>(14)?;
+    assert_eq!(
+        offsets.iter_values().filter(|&offset| offset != 0).count(),
+        8
+    );
+    assert_eq!(offsets[..2].iter_values().sum::<u32>(), 8192 + 10240);
+    assert_eq!(
+        offsets.into_flattened(),
+        vec![8192, 10240, 10752, 10880, 10912, 10920, 10928, 10936, 0, 0, 0, 0, 0, 0]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_read_up_to() -> Result<(), Error> {
+    let buf = [0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0xAA];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.remaining_count::<u16>()?, 3);
+    assert_eq!(reader.remaining_count::<u32>()?, 1);
+
+    // more than are available
+    assert_eq!(reader.read_up_to::<u16>(2)?, [1, 2]);
+    assert_eq!(reader.read_up_to::<u16>(10)?, [3]);
+    // the trailing partial value is left for the caller
+    assert_eq!(reader.cursor(), 6);
+    assert_eq!(reader.remaining_count::<u16>()?, 0);
+    assert_eq!(reader.read_up_to::<u16>(10)?, []);
+    assert_eq!(reader.remaining(), [0xAA]);
+
+    // exactly as many as are available
+    let mut reader = ByteReader::new(&buf[..6], Endianness::Little);
+    assert_eq!(reader.read_up_to::<u16>(3)?, [1, 2, 3]);
+    assert!(reader.is_empty());
+    assert_eq!(reader.read_up_to::<u16>(3)?, []);
+
+    // invalid values still error, without consuming
+    let mut reader = ByteReader::new(&[0x01, 0x02, 0x00], Endianness::Little);
+    assert!(matches!(
+        reader.read_up_to::<NonZeroU8>(3),
+        Err(Error {
+            kind: ErrorKind::Decode(TryFromBytesError::ZeroValue),
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 0);
+    assert!(matches!(
+        reader.remaining_count::<String>(),
+        Err(Error {
+            kind: ErrorKind::VariableSize,
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_read_n_view() -> Result<(), Error> {
+    let buf = [0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0xFF];
+    let mut reader = ByteReader::new(&buf, Endianness::Big);
+    let table = reader.read_n_view::<u16>(3)?;
+    assert_eq!(reader.cursor(), 6);
+    assert_eq!(table.len(), 3);
+    assert_eq!(table.get(0), Some(1));
+    assert_eq!(table.get(2), Some(3));
+    assert_eq!(table.get(3), None);
+    assert_eq!(table.get(usize::MAX), None);
+    assert_eq!(table.iter().len(), 3);
+    assert_eq!(table.into_iter().max(), Some(3));
+    assert_eq!(table.to_vec(), [1, 2, 3]);
+
+    // nothing is consumed when the view doesn't fit or a value is invalid
+    assert!(reader.read_n_view::<u16>(1).is_err());
+    let mut reader = ByteReader::new(b"\x00\x00\x00\x00\xFF\xFF\xFF\xFF", Endianness::Little);
+    assert!(reader.read_n_view::<char>(2).is_err());
+    assert_eq!(reader.cursor(), 0);
+    assert!(matches!(
+        reader.read_n_view::<String>(1).map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::VariableSize,
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn benchmark_read_n_view() -> Result<(), Error> {
+    let n = 1 << 20;
+    let data: Vec<u8> = (0..n as u32).flat_map(|i| i.to_le_bytes()).collect();
+    let mut timer = Timer::new();
+
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    let chomps: u64 = reader.read_n::<u32>(n)?.iter_values().map(u64::from).sum();
+    println!("reader.read_n::<u32>({})?: {:#?}", n, timer.time());
+
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    let view: u64 = reader.read_n_view::<u32>(n)?.iter().map(u64::from).sum();
+    println!("reader.read_n_view::<u32>({})?: {:#?}", n, timer.time());
+
+    assert_eq!(chomps, view);
+    Ok(())
+}
+
+#[test]
+fn test_read_sized_vector_hostile_count() -> Result<(), Error> {
+    let buf = [0xFE, 0xFF, 0xFF, 0xFF, 0x01, 0x02, 0x03, 0x04];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(matches!(
+        reader.read_sized_vector::<u32>().map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::DeclaredSizeTooLarge {
+                declared: 0xFFFFFFFE,
+                remaining: 4
+            },
+            position: Some(0),
+            ..
+        })
+    ));
+    // the count overflows when multiplied by the size of the values
+    let mut overflowing = ByteReader::new(&[0xFF; 16], Endianness::Little);
+    assert!(matches!(
+        overflowing.read_sized_vector_with::<u64, u64>().map(|_| ()),
+        Err(Error {
+            kind: ErrorKind::DeclaredSizeTooLarge { .. },
+            ..
+        })
+    ));
+    // variable size values can't be checked up front, but still error without allocating
+    assert!(reader.read_sized_vector::<String>().is_err());
+    assert_eq!(reader.cursor(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_seek_error() -> Result<(), Error> {
+    let buf = [0; 0x20];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    // seeking to the end is allowed, leaving nothing to read
+    reader.seek(buf.len())?;
+    assert!(reader.remaining().is_empty());
+    assert!(reader.read::<u8>().is_err());
+
+    let err = reader.seek(0x21).unwrap_err();
+    assert!(matches!(
+        err,
+        Error {
+            kind: ErrorKind::SeekOutOfRange {
+                position: 0x21,
+                len: 0x20
+            },
+            position: Some(0x20),
+            ..
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "seek to 0x21 is past the end of the buffer of length 0x20 at 0x20"
+    );
+    let err = reader
+        .seek_from(std::io::SeekFrom::Current(-0x30))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "seek to -0x10 is before the start of the buffer at 0x20"
+    );
+    assert!(reader.rebase(0x40).is_err());
+    assert_eq!(reader.cursor(), 0x20);
+    Ok(())
+}
+
+#[test]
+fn test_error_kind() -> Result<(), Error> {
+    // a count that's either a u16 or, in older files, a u8
+    fn read_count(reader: &mut ByteReader) -> Result<u16, Error> {
+        match reader.read_value::<u16>() {
+            Err(e) if e.kind().is_eof() => Ok(reader.read_value::<u8>()? as u16),
+            res => res,
+        }
+    }
+    assert_eq!(
+        read_count(&mut ByteReader::new(&[1, 2], Endianness::Little))?,
+        0x0201
+    );
+    assert_eq!(
+        read_count(&mut ByteReader::new(&[7], Endianness::Little))?,
+        7
+    );
+    assert!(read_count(&mut ByteReader::new(&[], Endianness::Little)).is_err());
+
+    let mut reader = ByteReader::new(&[0, 0, 0], Endianness::Little);
+    reader.seek(2)?;
+    let err = reader.read::<u16>().unwrap_err();
+    assert_eq!(err.position(), Some(2));
+    assert!(matches!(err.into_kind(), ErrorKind::UnexpectedEof { .. }));
+
+    // errors can be made outside of a reader
+    let err = Error::new(ErrorKind::NoPattern, Some(0x10));
+    assert!(matches!(err.kind(), ErrorKind::NoPattern));
+    assert_eq!(err.position(), Some(0x10));
+    Ok(())
+}
+
+#[test]
+fn test_error_source() {
+    use std::error::Error as _;
+
+    let buf = *b"\xFF\0";
+    let err = ByteReader::new(&buf, Endianness::Little)
+        .read::<String>()
+        .map(|_| ())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "failed to convert bytes at 0x0");
+    let source = err.source().unwrap();
+    assert_eq!(source.to_string(), "string isn't valid UTF-8");
+    assert!(source
+        .source()
+        .unwrap()
+        .downcast_ref::<std::string::FromUtf8Error>()
+        .is_some());
+
+    assert!(err.source().unwrap().is::<TryFromBytesError>());
+    let err = Error::from(std::io::Error::other("disk on fire"));
+    assert_eq!(err.to_string(), "failed to read from the source");
+    assert_eq!(err.source().unwrap().to_string(), "disk on fire");
+
+    let err = TryFromBytesError::Field {
+        name: "width",
+        offset: 4,
+        error: Box::new(TryFromBytesError::OutOfBounds),
+    };
+    assert_eq!(err.to_string(), "field `width` at 0x4 is invalid");
+    assert!(err.source().unwrap().is::<TryFromBytesError>());
+
+    let mut writer = ByteWriter::new(Endianness::Little);
+    let err = writer.append_pascal_string(&"a".repeat(256)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "string of 256 bytes is longer than the maximum of 255 at 0x0"
+    );
+
+    // each of them can be boxed into an anyhow::Error
+    let errors = [
+        anyhow::Error::from(err),
+        anyhow::Error::from(TryFromBytesError::ZeroValue),
+        anyhow::Error::from(Error::eof(0, 2, 1)),
+        anyhow::Error::from(Error::from(std::io::Error::other("disk on fire"))),
+    ];
+    assert_eq!(
+        errors[2].to_string(),
+        "2 bytes were requested but 1 remain at 0x0"
+    );
+}
+
+#[test]
+fn test_error_conversions() {
+    // io, UTF-8 and decoding errors all convert with ?
+    fn name(path: &str, buf: &[u8]) -> Result<String, Error> {
+        let mut bytes = std::fs::read(path)?;
+        bytes.extend_from_slice(buf);
+        let value = u16::try_from_bytes(bytes.clone(), Endianness::Little)?.0;
+        Ok(String::from_utf8(bytes)? + &value.to_string())
+    }
+
+    assert!(matches!(
+        name("test/missing.file", b""),
+        Err(Error {
+            kind: ErrorKind::Io(_),
+            position: None,
+            ..
+        })
+    ));
+    assert!(matches!(
+        name("/dev/null", b"\xFF\xFF"),
+        Err(Error {
+            kind: ErrorKind::Decode(TryFromBytesError::StringFromBytes(_)),
+            position: None,
+            ..
+        })
+    ));
+    assert!(matches!(
+        name("/dev/null", b"a"),
+        Err(Error {
+            kind: ErrorKind::Decode(TryFromBytesError::OutOfBounds),
+            ..
+        })
+    ));
+    assert_eq!(name("/dev/null", b"ab").unwrap(), "ab25185");
+
+    // readers and writers report where they failed
+    let mut writer = ByteWriter::new(Endianness::Little);
+    writer.append::<u32>(0);
+    let err = writer.write_vlq(u32::MAX).unwrap_err();
+    assert_eq!(err.position(), Some(4));
+    assert!(matches!(
+        err.kind(),
+        ErrorKind::Write(WriteErrorKind::VarintOverflow { .. })
+    ));
+}
+
+#[test]
+fn test_error_context() -> Result<(), Error> {
+    let mut buf = b"MATL".to_vec();
+    buf.extend_from_slice(&[0x04, 0x00, 0x00, 0x00]);
+    buf.extend_from_slice(&[0xAA; 14]);
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+
+    let err = reader
+        .with_context(format_args!("section '{}'", "MATL"), |r| {
+            r.expect_magic(b"MATL")?;
+            let count = r.read_value::<u32>()?;
+            for i in 0..count {
+                r.with_context(format_args!("entry {i}"), |r| r.read_value::<u32>())?;
+            }
+            Ok(())
+        })
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "while reading section 'MATL' entry 3: u32 needs 4 bytes but only 2 remain at 0x14"
+    );
+    assert_eq!(
+        err.context_labels().collect::<Vec<_>>(),
+        ["section 'MATL'", "entry 3"]
+    );
+    assert!(err.kind().is_eof());
+
+    // successful scopes leave no trace
+    reader.seek(0)?;
+    reader.with_context("magic", |r| r.expect_magic(b"MATL"))?;
+    let err = reader.read_n_values::<u32>(5).unwrap_err();
+    assert_eq!(err.context_labels().count(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_error_snippets() -> Result<(), Error> {
+    let buf = (0x40..0x80).collect::<Vec<u8>>();
+    let snippet = |err: Error| {
+        assert!(err.snippet().unwrap().bytes.len() <= 32);
+        err.to_string()
+            .lines()
+            .skip(1)
+            .map(str::to_owned)
+            .collect::<Vec<_>>()
+    };
+
+    // off by default
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert!(reader
+        .expect_magic(b"RIFF")
+        .unwrap_err()
+        .snippet()
+        .is_none());
+
+    let mut reader = reader.with_error_snippets(true);
+    assert_eq!(
+        snippet(reader.expect_magic(b"RIFF").unwrap_err()),
+        [
+            "00000000  40 41 42 43 44 45 46 47 48 49 4a 4b 4c 4d 4e 4f  |@ABCDEFGHIJKLMNO|",
+            "          ^^",
+        ]
+    );
+
+    reader.seek(0x25)?;
+    assert_eq!(
+        snippet(reader.expect_magic(b"RIFF").unwrap_err()),
+        [
+            "00000010                 55 56 57 58 59 5a 5b 5c 5d 5e 5f  |     UVWXYZ[\\]^_|",
+            "00000020  60 61 62 63 64 65 66 67 68 69 6a 6b 6c 6d 6e 6f  |`abcdefghijklmno|",
+            "                         ^^",
+            "00000030  70 71 72 73 74                                   |pqrst           |",
+        ]
+    );
+
+    reader.seek(0x3C)?;
+    assert_eq!(
+        snippet(reader.read_value::<u64>().unwrap_err()),
+        [
+            "00000020                                      6c 6d 6e 6f  |            lmno|",
+            "00000030  70 71 72 73 74 75 76 77 78 79 7a 7b 7c 7d 7e 7f  |pqrstuvwxyz{|}~.|",
+            "                                              ^^",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_error_codes() {
+    use std::time::UNIX_EPOCH;
+
+    let utf8 = String::from_utf8(vec![0xFF]).unwrap_err();
+    let utf16 = String::from_utf16(&[0xD800]).unwrap_err();
+    let kinds = [
+        (
+            ErrorKind::UnexpectedEof {
+                requested: 4,
+                available: 2,
+                type_name: "u32",
+            },
+            1,
+        ),
+        (
+            ErrorKind::Decode(TryFromBytesError::StringFromBytes(utf8)),
+            2,
+        ),
+        (ErrorKind::UnterminatedString { scanned: 0 }, 5),
+        (ErrorKind::NoDelimiter { delimiter: b',' }, 6),
+        (
+            ErrorKind::Decode(TryFromBytesError::NonZeroPadding { offset: 0, byte: 1 }),
+            7,
+        ),
+        (
+            ErrorKind::InvalidEncoding {
+                encoding: "Shift_JIS",
+                offset: 0,
+            },
+            8,
+        ),
+        (ErrorKind::TimestampOutOfRange, 9),
+        (
+            ErrorKind::InvalidBcd {
+                offset: 0,
+                nibble: 0xA,
+            },
+            10,
+        ),
+        (ErrorKind::BcdOverflow, 11),
+        (ErrorKind::UnconsumedBytes { remaining: 1 }, 12),
+        (ErrorKind::NoSentinel { read: 0 }, 13),
+        (
+            ErrorKind::SeekOutOfRange {
+                position: -1,
+                len: 0,
+            },
+            14,
+        ),
+        (
+            ErrorKind::SplitOutOfRange {
+                offset: 1,
+                remaining: 0,
+            },
+            15,
+        ),
+        (
+            ErrorKind::StaleMark {
+                position: 0,
+                origin: 1,
+            },
+            16,
+        ),
+        (
+            ErrorKind::BadMagic {
+                expected: vec![],
+                actual: vec![],
+            },
+            17,
+        ),
+        (ErrorKind::NoPattern, 18),
+        (ErrorKind::PastEnd { overrun: 1 }, 19),
+        (ErrorKind::EmptyPositionStack, 20),
+        (ErrorKind::VariableSize, 21),
+        (
+            ErrorKind::DeclaredSizeTooLarge {
+                declared: 2,
+                remaining: 1,
+            },
+            22,
+        ),
+        (ErrorKind::LengthOverflow, 23),
+        (ErrorKind::VarintOverflow, 24),
+        (ErrorKind::NoBits { bit: 0 }, 25),
+        (
+            ErrorKind::Decode(TryFromBytesError::StringFromUtf16Bytes(utf16)),
+            26,
+        ),
+        (ErrorKind::Decode(TryFromBytesError::ArrayFromSlice), 27),
+        (ErrorKind::Decode(TryFromBytesError::OutOfBounds), 28),
+        (ErrorKind::Decode(TryFromBytesError::InvalidBool(2)), 29),
+        (
+            ErrorKind::Decode(TryFromBytesError::InvalidChar(0xD800)),
+            30,
+        ),
+        (ErrorKind::Decode(TryFromBytesError::ZeroValue), 31),
+        (
+            ErrorKind::Decode(TryFromBytesError::InvalidDiscriminant {
+                value: 9,
+                type_name: "Kind",
+            }),
+            32,
+        ),
+        (ErrorKind::Io(std::io::Error::other("disk on fire")), 33),
+        (ErrorKind::Write(WriteErrorKind::Fail), 34),
+        (
+            ErrorKind::Write(WriteErrorKind::StringTooLong { len: 2, max: 1 }),
+            35,
+        ),
+        (
+            ErrorKind::Write(WriteErrorKind::UnmappableChar {
+                ch: '☃',
+                encoding: "windows-1252",
+            }),
+            36,
+        ),
+        (
+            ErrorKind::Decode(TryFromBytesError::custom(std::fmt::Error)),
+            37,
+        ),
+        (
+            ErrorKind::Write(WriteErrorKind::WriteOutOfRange {
+                position: 4,
+                len: 4,
+                buf_len: 4,
+            }),
+            38,
+        ),
+        (ErrorKind::InvalidData("bad".to_owned()), 39),
+        (
+            ErrorKind::InvalidAlignment {
+                origin: 0,
+                alignment: 0,
+            },
+            40,
+        ),
+        (
+            ErrorKind::UnsupportedEncoding {
+                encoding: "UTF-16LE",
+            },
+            41,
+        ),
+        (ErrorKind::Custom("bad".to_owned()), 37),
+        // the same condition has the same code whichever way it happened
+        (
+            ErrorKind::Write(WriteErrorKind::UnsupportedEncoding {
+                encoding: "UTF-16LE",
+            }),
+            41,
+        ),
+        (
+            ErrorKind::Write(WriteErrorKind::TimestampOutOfRange { time: UNIX_EPOCH }),
+            9,
+        ),
+        (
+            ErrorKind::Write(WriteErrorKind::BcdOverflow { value: 100, len: 1 }),
+            11,
+        ),
+        (
+            ErrorKind::Write(WriteErrorKind::LengthOverflow { len: 256 }),
+            23,
+        ),
+        (
+            ErrorKind::Write(WriteErrorKind::VarintOverflow { value: 1 << 28 }),
+            24,
+        ),
+        // wrappers have the code of the error they hold
+        (
+            ErrorKind::InSegment {
+                segment: 1,
+                error: Box::new(ErrorKind::PastEnd { overrun: 1 }),
+            },
+            19,
+        ),
+        (
+            ErrorKind::Decode(TryFromBytesError::Field {
+                name: "width",
+                offset: 4,
+                error: Box::new(TryFromBytesError::Element {
+                    index: 0,
+                    offset: 0,
+                    error: Box::new(TryFromBytesError::ZeroValue),
+                }),
+            }),
+            31,
+        ),
+    ];
+    for (kind, code) in kinds {
+        assert_eq!(kind.code() as u16, code, "{kind:?}");
+    }
+
+    let err = ByteReader::new(&[0x01], Endianness::Little)
+        .read_value::<u16>()
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnexpectedEof);
+    assert!(err.is_eof() && err.code().is_eof());
+    assert!(!ErrorCode::InvalidUtf8.is_eof());
+}
+
+#[test]
+fn test_io_error_conversion() {
+    use std::io;
+
+    fn read_pair(buf: &[u8]) -> io::Result<(u8, bool)> {
+        let mut reader = ByteReader::new(buf, Endianness::Little);
+        Ok((reader.read_value::<u8>()?, reader.read_strict_bool()?))
+    }
+
+    let err = read_pair(&[0x01]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    // a lookahead past the end is running out of bytes too
+    let reader = ByteReader::new(&[0x01], Endianness::Little);
+    let err = io::Error::from(reader.peek_at::<u16>(1).unwrap_err());
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    let err = read_pair(&[0x01, 0x02]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.get_ref().unwrap().is::<Error>());
+
+    // the original error can be downcast back out
+    let inner = err.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert!(matches!(
+        *inner,
+        Error {
+            kind: ErrorKind::Decode(TryFromBytesError::InvalidBool(2)),
+            position: Some(1),
+            ..
+        }
+    ));
+
+    // and converting back with ? doesn't nest it
+    let roundtrip = Error::from(io::Error::from(Error::new(ErrorKind::NoPattern, Some(3))));
+    assert!(matches!(
+        roundtrip,
+        Error {
+            kind: ErrorKind::NoPattern,
+            position: Some(3),
+            ..
+        }
+    ));
+
+    let mut writer = ByteWriter::new(Endianness::Little);
+    let err = io::Error::from(writer.write::<u8>(1, 0).unwrap_err());
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = io::Error::from(Error::from(io::Error::from(io::ErrorKind::BrokenPipe)));
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn test_error_constructors() -> crate::Result<()> {
+    let err = Error::eof(0x10, 4, 1);
+    assert!(matches!(
+        err,
+        Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 4,
+                available: 1,
+                type_name: ""
+            },
+            position: Some(0x10),
+            ..
+        }
+    ));
+    assert!(err.is_eof());
+    assert_eq!(err.code(), ErrorCode::UnexpectedEof);
+    assert_eq!(
+        err.to_string(),
+        "4 bytes were requested but 1 remain at 0x10"
+    );
+
+    let err = Error::invalid_data(format_args!("checksum {:#x} doesn't match", 0xBEEF));
+    assert!(
+        matches!(err.kind(), ErrorKind::InvalidData(message) if message == "checksum 0xbeef doesn't match")
+    );
+    assert_eq!(err.position(), None);
+    assert_eq!(err.to_string(), "checksum 0xbeef doesn't match");
+    assert_eq!(
+        std::io::Error::from(err).kind(),
+        std::io::ErrorKind::InvalidData
+    );
+
+    let err = Error::custom("unsupported version 7");
+    assert!(matches!(err.kind(), ErrorKind::Custom(_)));
+    assert_eq!(err.code(), ErrorCode::Custom);
+    assert_eq!(err.to_string(), "unsupported version 7");
+    assert_eq!(std::io::Error::from(err).kind(), std::io::ErrorKind::Other);
+
+    // they compose with context like any other error
+    let result: crate::Result<u8> = Err(Error::custom("unsupported version 7"));
+    let err = result.map_err(|e| e.with_context("header")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "while reading header: unsupported version 7"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unexpected_eof() {
+    let buf = [0; 16];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    reader.seek(4).unwrap();
+    let err = reader.read_n::<u32>(1000).map(|_| ()).unwrap_err();
+    assert!(matches!(
+        err,
+        Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 4000,
+                available: 12,
+                type_name: "u32"
+            },
+            position: Some(4),
+            ..
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "u32 needs 4000 bytes but only 12 remain at 0x4"
+    );
+
+    // an unterminated string claims one more byte than is left
+    let mut reader = ByteReader::new(b"abc", Endianness::Little);
+    assert!(matches!(
+        reader.read_string(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 4,
+                available: 3,
+                type_name: "alloc::string::String"
+            },
+            position: Some(0),
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 0);
+}
+
+#[test]
+fn test_infallible_conversion() -> Result<(), Error> {
+    // only the conversion compiling matters, as an Infallible can't be made
+    let value: Result<u8, std::convert::Infallible> = Ok(1);
+    assert_eq!(value?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_endianness_parse() {
+    for s in ["le", "LE", "little", "Little-Endian", "little_endian"] {
+        assert_eq!(s.parse(), Ok(Endianness::Little), "{s}");
+    }
+    for s in ["be", "BE", "big", "BIG-ENDIAN", "big_endian"] {
+        assert_eq!(s.parse(), Ok(Endianness::Big), "{s}");
+    }
+    for s in ["", "middle", "l", "network", " le"] {
+        assert!(s.parse::<Endianness>().is_err(), "{s}");
+    }
+
+    let err: ParseEndiannessError = "middle".parse::<Endianness>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown endianness \"middle\", expected little or big"
+    );
+}
+
+#[test]
+fn test_endianness_display() {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        assert_eq!(endianness.to_string().parse(), Ok(endianness));
+        assert_eq!(endianness.swapped().swapped(), endianness);
+        assert_ne!(endianness.swapped(), endianness);
+    }
+    assert_eq!(Endianness::Little.to_string(), "little");
+    assert_eq!(Endianness::Big.to_string(), "big");
+    assert_eq!(format!("{:?}", Endianness::Big), "Big");
+
+    let set: std::collections::HashSet<_> = [Endianness::Little, Endianness::Big, Endianness::Big]
+        .into_iter()
+        .collect();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn test_read_string_lenient() -> Result<(), Error> {
+    // terminated strings read the same either way
+    let data = *b"name\0";
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert_eq!(reader.read_string_lenient()?, "name");
+    assert!(reader.is_empty());
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert_eq!(reader.read_string()?, "name");
+    assert!(reader.is_empty());
+
+    // only the lenient read accepts a missing terminator
+    let data = *b"trunc";
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(matches!(
+        reader.read_string().unwrap_err().kind,
+        ErrorKind::UnexpectedEof { .. }
+    ));
+    assert!(matches!(
+        reader.read_cstr().unwrap_err().kind,
+        ErrorKind::UnterminatedString { scanned: 5 }
+    ));
+    assert_eq!(reader.cursor(), 0);
+    assert_eq!(reader.read_string_lenient()?, "trunc");
+    assert!(reader.is_empty());
+
+    // an empty buffer has no string, even leniently
+    assert!(matches!(
+        reader.read_string_lenient(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof {
+                requested: 1,
+                available: 0,
+                ..
+            },
+            position: Some(5),
+            ..
+        })
+    ));
+    assert!(reader.read_string().is_err());
+
+    let data = [0x61, 0xFF];
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    assert!(reader.read_string_lenient().is_err());
+    assert_eq!(reader.cursor(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_utf8() -> Result<(), Error> {
+    // 0xE2 starts a three byte character but 0x28 isn't a continuation byte
+    let data = *b"\x01\x02\x03\x04ok\0ab\xE2\x28\xA1c\0";
+    let mut reader = ByteReader::new(&data, Endianness::Little);
+    reader.rebase(4)?;
+    assert_eq!(reader.read_string()?, "ok");
+
+    let err = reader.read_string().unwrap_err();
+    let ErrorKind::InvalidUtf8 { offset, error } = err.kind() else {
+        panic!("expected InvalidUtf8, got {err:?}");
+    };
+    assert_eq!(*offset, 9);
+    assert_eq!(err.position(), Some(7));
+    assert_eq!(error.as_bytes(), b"ab\xE2\x28\xA1c");
+    assert_eq!(err.code(), ErrorCode::InvalidUtf8);
+    assert!(std::error::Error::source(&err).is_some());
+    assert_eq!(err.to_string(), "string isn't valid UTF-8 from 0x9 at 0x7");
+    assert_eq!(reader.cursor(), 3);
+
+    assert_eq!(reader.read_string_lossy()?, "ab\u{FFFD}(\u{FFFD}c");
+    assert!(reader.is_empty());
+
+    // the lossy read still needs a terminator
+    let mut reader = ByteReader::new(b"\xFF", Endianness::Little);
+    assert!(matches!(
+        reader.read_string_lossy(),
+        Err(Error {
+            kind: ErrorKind::UnexpectedEof { .. },
+            ..
+        })
+    ));
+    assert_eq!(reader.cursor(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_bytewriter_buffer_access() {
+    let mut writer = ByteWriter::new(Endianness::Little);
+    writer.append::<u32>(0xDEADBEEF);
+    assert_eq!(writer.as_bytes(), [0xEF, 0xBE, 0xAD, 0xDE]);
+
+    // the borrowed and returned bytes are the writer's own buffer, not a copy
+    let ptr = writer.as_bytes().as_ptr();
+    let copy = writer.buf();
+    assert_ne!(copy.as_ptr(), ptr);
+    let taken = writer.take();
+    assert_eq!(taken.as_ptr(), ptr);
+    assert_eq!(taken, [0xEF, 0xBE, 0xAD, 0xDE]);
+
+    // take leaves an empty writer with the same endianness
+    assert!(writer.is_empty());
+    assert!(writer.as_bytes().is_empty());
+    writer.append::<u16>(0x0102);
+    assert_eq!(writer.as_bytes(), [0x02, 0x01]);
+
+    let ptr = writer.as_bytes().as_ptr();
+    let inner = writer.into_inner();
+    assert_eq!(inner.as_ptr(), ptr);
+    assert_eq!(inner, [0x02, 0x01]);
+}
+
+#[test]
+fn test_append_borrowed() -> Result<(), Error> {
+    let blob: &[u8] = &[0xDE, 0xAD, 0xBE, 0xEF];
+    let owned = String::from("owned");
+    let mut writer = ByteWriter::new(Endianness::Little);
+    assert_eq!(writer.append("name"), 5);
+    assert_eq!(writer.append(std::borrow::Cow::Borrowed("cow")), 4);
+    assert_eq!(writer.append_ref(&owned), 6);
+    assert_eq!(writer.append::<u16>(blob.len() as u16), 2);
+    assert_eq!(writer.append(blob), 4);
+    assert_eq!(writer.append(vec![0x01, 0x02]), 2);
+    assert_eq!(owned, "owned");
+
+    let buf = writer.into_inner();
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(reader.read_string()?, "name");
+    assert_eq!(reader.read_string()?, "cow");
+    assert_eq!(reader.read_string()?, "owned");
+    let len = reader.read_value::<u16>()? as usize;
+    assert_eq!(reader.read_slice(len)?, blob);
+    // byte blobs have no terminator, so the rest of the buffer is the last one
+    assert_eq!(reader.remaining(), [0x01, 0x02]);
+    Ok(())
+}
+
+#[test]
+fn test_portable_lengths() -> Result<(), Error> {
+    // the same count written as it would be by 32 and 64-bit targets, with explicit widths
+    let count = 3usize;
+    let mut narrow = ByteWriter::new(Endianness::Little);
+    narrow.append::<u32>(count.try_into().unwrap());
+    let mut wide = ByteWriter::new(Endianness::Little);
+    wide.append::<u64>(count as u64);
+    assert_eq!(narrow.len(), 4);
+    assert_eq!(wide.len(), 8);
+
+    let narrow = narrow.into_inner();
+    let wide = wide.into_inner();
+    let mut reader = ByteReader::new(&narrow, Endianness::Little);
+    assert_eq!(
+        usize::try_from(reader.read_value::<u32>()?).ok(),
+        Some(count)
+    );
+    let mut reader = ByteReader::new(&wide, Endianness::Little);
+    assert_eq!(
+        usize::try_from(reader.read_value::<u64>()?).ok(),
+        Some(count)
+    );
+
+    // a 64-bit length too large for a 32-bit target errors rather than truncating
+    let too_large = (u32::MAX as u64 + 1).to_le_bytes();
+    let mut reader = ByteReader::new(&too_large, Endianness::Little);
+    assert!(u32::try_from(reader.read_value::<u64>()?).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_streaming_sized_vector_limit() -> Result<(), Error> {
+    use std::io::{Cursor, Read};
+
+    let mut writer = ByteWriter::new(Endianness::Little);
+    writer.append(2u32);
+    writer.append("albedo");
+    writer.append("normal");
+    writer.append(3u32);
+    for value in [10u16, 20, 30] {
+        writer.append(value);
+    }
+    let buf = writer.into_inner();
+    let mut reader = StreamingByteReader::new(buf.as_slice(), Endianness::Little)
+        .with_chunk_size(1)
+        .with_max_buffer(18);
+    assert_eq!(reader.read_sized_vector::<String>()?, ["albedo", "normal"]);
+    assert_eq!(reader.read_sized_vector::<u16>()?, [10, 20, 30]);
+
+    // a hostile count fails without buffering the endless stream behind it
+    let source = Cursor::new(u32::MAX.to_le_bytes()).chain(std::io::repeat(0));
+    let mut reader = StreamingByteReader::new(source, Endianness::Little);
+    assert!(matches!(
+        reader.read_sized_vector::<u32>(),
+        Err(Error {
+            kind: ErrorKind::DeclaredSizeTooLarge {
+                declared: 0xFFFF_FFFF,
+                remaining: 0x400_0000,
+            },
+            position: Some(0),
+            ..
+        })
+    ));
+    assert_eq!(reader.position(), 0);
+
+    // variable size values can't be checked up front, but stop at the limit
+    let source = Cursor::new(1u32.to_le_bytes()).chain(std::io::repeat(b'a'));
+    let mut reader = StreamingByteReader::new(source, Endianness::Little)
+        .with_chunk_size(64)
+        .with_max_buffer(1024);
+    assert!(matches!(
+        reader.read_sized_vector::<String>(),
+        Err(Error {
+            kind: ErrorKind::DeclaredSizeTooLarge { declared: 1, .. },
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_eof_details() {
+    fn eof(result: Result<impl std::fmt::Debug, Error>) -> (usize, usize, &'static str) {
+        match result.unwrap_err().kind {
+            ErrorKind::UnexpectedEof {
+                requested,
+                available,
+                type_name,
+            } => (requested, available, type_name),
+            kind => panic!("expected UnexpectedEof, got {kind:?}"),
+        }
+    }
+
+    let buf = [0x06, b'a', b'b', 0x00, 0x00, 0x00];
+    let reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(eof(reader.clone().skip_n::<u32>(2)), (8, 6, "u32"));
+    assert_eq!(eof(reader.peek_bytes::<8>()), (8, 6, "[u8; 8]"));
+    assert_eq!(eof(reader.clone().expect_magic(b"MAGIC!!")), (7, 6, "[u8]"));
+    assert_eq!(
+        eof(reader.clone().read_string_fixed(7)),
+        (7, 6, "alloc::string::String")
+    );
+    assert_eq!(
+        eof(reader.clone().read_pascal_string()),
+        (7, 6, "alloc::string::String")
+    );
+    assert_eq!(eof(reader.clone().read_f32_slice(2)), (8, 6, "f32"));
+    assert_eq!(eof(reader.clone().read_f64_slice(1)), (8, 6, "f64"));
+
+    // a UTF-16 string without its terminator claims one after the whole units there are
+    let buf = [b'h', 0x00, b'i', 0x00, 0xFF];
+    let mut reader = ByteReader::new(&buf, Endianness::Little);
+    assert_eq!(
+        eof(reader.read_utf16_string()),
+        (6, 5, "alloc::string::String")
+    );
+    assert_eq!(eof(reader.read_bcd(6)), (6, 5, "[u8]"));
+    assert_eq!(reader.cursor(), 0);
+}
@@ -0,0 +1,103 @@
+//! time.rs
+use std::{
+    io::BufRead,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::{
+    ByteReader, ByteReaderResource, ByteWriter, ByteWriterResource, Error, ErrorKind,
+    WriteErrorKind,
+};
+
+impl<'a> ByteReader<'a> {
+    /// Reads a T holding seconds since the unix epoch
+    ///
+    /// Errors with `TimestampOutOfRange` if the time can't be represented by a SystemTime,
+    /// leaving the cursor untouched.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x80, 0x51, 0x01, 0x00];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     let time = reader.read_unix_timestamp_secs::<u32>()?;
+    ///     assert_eq!(time, UNIX_EPOCH + Duration::from_secs(86400));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_unix_timestamp_secs<T>(&mut self) -> Result<SystemTime, Error>
+    where
+        T: ByteReaderResource<'a> + Into<u64>,
+    {
+        self.read_unix_timestamp::<T>(Duration::from_secs)
+    }
+
+    /// Reads a T holding milliseconds since the unix epoch
+    ///
+    /// Errors in the same way as `read_unix_timestamp_secs`.
+    pub fn read_unix_timestamp_millis<T>(&mut self) -> Result<SystemTime, Error>
+    where
+        T: ByteReaderResource<'a> + Into<u64>,
+    {
+        self.read_unix_timestamp::<T>(Duration::from_millis)
+    }
+
+    fn read_unix_timestamp<T>(&mut self, unit: fn(u64) -> Duration) -> Result<SystemTime, Error>
+    where
+        T: ByteReaderResource<'a> + Into<u64>,
+    {
+        let value = self.peek::<T>()?.inner().into();
+        let time = UNIX_EPOCH
+            .checked_add(unit(value))
+            .ok_or_else(|| self.err(ErrorKind::TimestampOutOfRange))?;
+        self.consume(T::SIZE);
+        Ok(time)
+    }
+}
+
+impl ByteWriter {
+    /// Appends a time as seconds since the unix epoch stored in a T, returning the bytes written
+    ///
+    /// Errors if the time is before the epoch or too far after it to fit in a T. Any
+    /// fraction of a second is truncated.
+    pub fn append_unix_timestamp_secs<T>(&mut self, time: SystemTime) -> Result<usize, Error>
+    where
+        T: ByteWriterResource + TryFrom<u64>,
+    {
+        self.append_unix_timestamp::<T>(time, self.since_epoch(time)?.as_secs())
+    }
+
+    /// Appends a time as milliseconds since the unix epoch stored in a T, returning the bytes written
+    ///
+    /// Errors in the same way as `append_unix_timestamp_secs`.
+    pub fn append_unix_timestamp_millis<T>(&mut self, time: SystemTime) -> Result<usize, Error>
+    where
+        T: ByteWriterResource + TryFrom<u64>,
+    {
+        let millis = u64::try_from(self.since_epoch(time)?.as_millis())
+            .map_err(|_| self.err(WriteErrorKind::TimestampOutOfRange { time }))?;
+        self.append_unix_timestamp::<T>(time, millis)
+    }
+
+    fn since_epoch(&self, time: SystemTime) -> Result<Duration, Error> {
+        time.duration_since(UNIX_EPOCH)
+            .map_err(|_| self.err(WriteErrorKind::TimestampOutOfRange { time }))
+    }
+
+    fn append_unix_timestamp<T>(&mut self, time: SystemTime, value: u64) -> Result<usize, Error>
+    where
+        T: ByteWriterResource + TryFrom<u64>,
+    {
+        let value = T::try_from(value)
+            .map_err(|_| self.err(WriteErrorKind::TimestampOutOfRange { time }))?;
+        Ok(self.append::<T>(value))
+    }
+}
@@ -1,42 +1,175 @@
 //! transmutable.rs
-use std::{io, mem::size_of, string::FromUtf8Error};
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt,
+    mem::size_of,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+        NonZeroU32, NonZeroU64, NonZeroU8,
+    },
+    str::FromStr,
+    string::{FromUtf16Error, FromUtf8Error},
+};
 
-use super::{bytereader::ByteReaderError, bytewriter::ByteWriterError};
-
-#[derive(PartialEq, Clone, Copy, Default)]
+/// The byte order values are stored in
+///
+/// Parses from the usual spellings, case insensitively, and displays as `little` or `big`:
+/// ```
+/// use bitchomp::Endianness;
+///
+/// let endianness: Endianness = "BE".parse().unwrap();
+/// assert_eq!(endianness, Endianness::Big);
+/// assert_eq!(endianness.to_string(), "big");
+/// assert_eq!(endianness.swapped(), Endianness::Little);
+/// assert!("middle".parse::<Endianness>().is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 pub enum Endianness {
     #[default]
     Little,
     Big,
 }
 
-trait TInt {}
-impl !TInt for String {}
-impl !TInt for &str {}
+impl Endianness {
+    /// Returns the endianness of the target platform
+    pub const fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+
+    /// Returns the opposite endianness
+    pub const fn swapped(self) -> Self {
+        match self {
+            Endianness::Little => Endianness::Big,
+            Endianness::Big => Endianness::Little,
+        }
+    }
+}
+
+impl fmt::Display for Endianness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Endianness::Little => "little",
+            Endianness::Big => "big",
+        })
+    }
+}
+
+impl FromStr for Endianness {
+    type Err = ParseEndiannessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "le" | "little" | "little-endian" | "little_endian" => Ok(Endianness::Little),
+            "be" | "big" | "big-endian" | "big_endian" => Ok(Endianness::Big),
+            _ => Err(ParseEndiannessError(s.to_string())),
+        }
+    }
+}
+
+/// The error returned when a string isn't a recognised endianness
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseEndiannessError(String);
+
+impl fmt::Display for ParseEndiannessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown endianness {:?}, expected little or big", self.0)
+    }
+}
+
+impl Error for ParseEndiannessError {}
+
+trait TNum {}
+impl !TNum for String {}
+impl !TNum for Cow<'_, str> {}
+impl !TNum for &str {}
+impl<T> !TNum for &[T] {}
+impl<T> !TNum for Vec<T> {}
+impl<T, const N: usize> !TNum for [T; N] {}
 
-impl TInt for usize {}
-impl TInt for u8 {}
-impl TInt for u16 {}
-impl TInt for u32 {}
-impl TInt for u64 {}
-impl TInt for u128 {}
-impl TInt for isize {}
-impl TInt for i8 {}
-impl TInt for i16 {}
-impl TInt for i32 {}
-impl TInt for i64 {}
-impl TInt for i128 {}
+// usize and isize vary in width between platforms, so values written on one couldn't be
+// read on another
+impl !TNum for usize {}
+impl !TNum for isize {}
 
+impl TNum for u8 {}
+impl TNum for u16 {}
+impl TNum for u32 {}
+impl TNum for u64 {}
+impl TNum for u128 {}
+impl TNum for i8 {}
+impl TNum for i16 {}
+impl TNum for i32 {}
+impl TNum for i64 {}
+impl TNum for i128 {}
+impl TNum for f32 {}
+impl TNum for f64 {}
+
+/// A type that can be read from bytes
+///
+/// `usize` and `isize` aren't implemented, as their width depends on the platform: a
+/// `usize` written on a 64-bit target is 8 bytes, which a 32-bit target would read as 4.
+/// Code that read them before should read the width the format was written with, and
+/// convert with `try_into`, which errors rather than truncating if the value doesn't fit:
+/// ```
+/// #![feature(generic_const_exprs)]
+///
+/// use bitchomp::{ByteReader, Endianness, Error, ErrorKind};
+///
+/// fn main() -> Result<(), Error> {
+///     let buf = [0x2A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+///
+///     let len: usize = reader
+///         .read_value::<u64>()?
+///         .try_into()
+///         .map_err(|_| reader.err(ErrorKind::LengthOverflow))?;
+///     assert_eq!(len, 42);
+///
+///     Ok(())
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #![feature(generic_const_exprs)]
+///
+/// use bitchomp::{ByteReader, Endianness};
+///
+/// let buf = [0x00; 8];
+/// ByteReader::new(&buf, Endianness::Little).read_value::<usize>();
+/// ```
 pub trait TryFromBytes: Sized {
     type Bytes;
     type Error = TryFromBytesError;
 
+    /// The number of bytes a value of Self occupies in the buffer
+    const SIZE: usize = size_of::<Self>();
+
+    /// Whether values of Self occupy a varying number of bytes, like NUL-terminated
+    /// Strings, in which case SIZE is meaningless and each value is decoded to find its size
+    const VARIABLE_SIZE: bool = false;
+
     /// Attempts to convert from the byte format to Self
     /// returning the number
     fn try_from_bytes(
         bytes: Self::Bytes,
         endianness: Endianness,
     ) -> Result<(Self, usize), Self::Error>;
+
+    /// Attempts to convert from a slice of the byte format to Self
+    ///
+    /// Types that can decode straight from the slice override this to skip converting it
+    /// to `Self::Bytes`, which for most types is an allocation.
+    fn try_from_slice(bytes: &[u8], endianness: Endianness) -> Result<(Self, usize), Self::Error>
+    where
+        Self::Bytes: for<'b> From<&'b [u8]>,
+    {
+        Self::try_from_bytes(bytes.into(), endianness)
+    }
 }
 
 // pub trait FromBytes: Sized {
@@ -54,6 +187,25 @@ pub trait TryFromBytes: Sized {
 //     }
 // }
 
+/// A type that can be written as bytes
+///
+/// Like `TryFromBytes`, this isn't implemented for `usize` or `isize`. Write them as the
+/// fixed width the format uses instead, converting with `try_into`:
+/// ```
+/// use bitchomp::{ByteWriter, Endianness};
+///
+/// let names = ["albedo", "normal"];
+/// let mut writer = ByteWriter::new(Endianness::Little);
+///
+/// writer.append::<u32>(names.len().try_into().unwrap());
+/// assert_eq!(writer.as_bytes(), [0x02, 0x00, 0x00, 0x00]);
+/// ```
+///
+/// ```compile_fail
+/// use bitchomp::{ByteWriter, Endianness};
+///
+/// ByteWriter::new(Endianness::Little).append::<usize>(2);
+/// ```
 pub trait ToBytes: Sized {
     type Bytes;
     fn to_bytes(&self, endianness: Endianness) -> Self::Bytes;
@@ -61,10 +213,95 @@ pub trait ToBytes: Sized {
 
 // trait Transmutable = ToBytes + FromBytes;
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum TryFromBytesError {
     StringFromBytes(FromUtf8Error),
+    StringFromUtf16Bytes(FromUtf16Error),
     ArrayFromSlice,
     OutOfBounds,
+    InvalidBool(u8),
+    InvalidChar(u32),
+    ZeroValue,
+    /// The padding byte at `offset` is `byte` rather than 0x00. The offset is into the
+    /// value, or the buffer for `ByteReader::read_padded_string`
+    NonZeroPadding {
+        offset: usize,
+        byte: u8,
+    },
+    /// The field `name` of a struct, starting `offset` bytes in, failed to convert
+    Field {
+        name: &'static str,
+        offset: usize,
+        error: Box<TryFromBytesError>,
+    },
+    /// `value` isn't the discriminant of any variant of the enum `type_name`
+    InvalidDiscriminant {
+        value: i128,
+        type_name: &'static str,
+    },
+    /// Element `index` of a compound value, starting `offset` bytes in, failed to convert
+    Element {
+        index: usize,
+        offset: usize,
+        error: Box<TryFromBytesError>,
+    },
+    /// A type's own error, for conversions which can fail in ways not covered above
+    Custom(Box<dyn Error + Send + Sync>),
+}
+
+impl TryFromBytesError {
+    /// Wraps the error of a TryFromBytes impl, unless it's a TryFromBytesError already
+    ///
+    /// The wrapped error can be recovered with `source` and `downcast_ref`.
+    pub fn custom(err: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        match err.into().downcast::<TryFromBytesError>() {
+            Ok(err) => *err,
+            Err(err) => TryFromBytesError::Custom(err),
+        }
+    }
+}
+
+impl fmt::Display for TryFromBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TryFromBytesError::*;
+        match self {
+            StringFromBytes(_) => write!(f, "string isn't valid UTF-8"),
+            StringFromUtf16Bytes(_) => write!(f, "string isn't valid UTF-16"),
+            ArrayFromSlice => write!(f, "slice is the wrong length for the array"),
+            OutOfBounds => write!(f, "not enough bytes for the value"),
+            InvalidBool(byte) => write!(f, "{byte:#04x} isn't a valid bool"),
+            InvalidChar(code) => write!(f, "{code:#x} isn't a valid char"),
+            ZeroValue => write!(f, "value of a NonZero integer is zero"),
+            NonZeroPadding { offset, byte } => {
+                write!(
+                    f,
+                    "padding byte at {offset:#x} is {byte:#04x} rather than 0x00"
+                )
+            }
+            Field { name, offset, .. } => write!(f, "field `{name}` at {offset:#x} is invalid"),
+            InvalidDiscriminant { value, type_name } => {
+                write!(f, "{value} isn't a discriminant of {type_name}")
+            }
+            Element { index, offset, .. } => {
+                write!(f, "element {index} at {offset:#x} is invalid")
+            }
+            Custom(_) => write!(f, "value is invalid"),
+        }
+    }
+}
+
+impl Error for TryFromBytesError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TryFromBytesError::StringFromBytes(err) => Some(err),
+            TryFromBytesError::StringFromUtf16Bytes(err) => Some(err),
+            TryFromBytesError::Field { error, .. } | TryFromBytesError::Element { error, .. } => {
+                Some(error.as_ref())
+            }
+            TryFromBytesError::Custom(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
 }
 
 impl From<FromUtf8Error> for TryFromBytesError {
@@ -72,10 +309,18 @@ impl From<FromUtf8Error> for TryFromBytesError {
         Self::StringFromBytes(err)
     }
 }
+
+impl From<FromUtf16Error> for TryFromBytesError {
+    fn from(err: FromUtf16Error) -> Self {
+        Self::StringFromUtf16Bytes(err)
+    }
+}
 impl TryFromBytes for String {
     type Bytes = Vec<u8>;
     type Error = TryFromBytesError;
 
+    const VARIABLE_SIZE: bool = true;
+
     fn try_from_bytes(bytes: Self::Bytes, _: Endianness) -> Result<(Self, usize), Self::Error> {
         let mut vec: Vec<u8> = Vec::new();
         for byte in bytes {
@@ -90,7 +335,175 @@ impl TryFromBytes for String {
     }
 }
 
-impl<T: num_traits::FromBytes<Bytes = [u8; size_of::<T>()]> + TInt> TryFromBytes for T
+/// Booleans are read leniently: `0x00` is false and any other byte is true.
+/// Use `ByteReader::read_strict_bool` to reject bytes other than `0x00`/`0x01`.
+impl TryFromBytes for bool {
+    type Bytes = Vec<u8>;
+    type Error = TryFromBytesError;
+
+    fn try_from_bytes(bytes: Self::Bytes, _: Endianness) -> Result<(Self, usize), Self::Error> {
+        match bytes.first() {
+            Some(&byte) => Ok((byte != 0x00, 1)),
+            None => Err(TryFromBytesError::OutOfBounds),
+        }
+    }
+}
+
+/// Chars are stored as a 4 byte unicode code point
+impl TryFromBytes for char {
+    type Bytes = Vec<u8>;
+    type Error = TryFromBytesError;
+
+    fn try_from_bytes(
+        bytes: Self::Bytes,
+        endianness: Endianness,
+    ) -> Result<(Self, usize), Self::Error> {
+        let (code, size) = u32::try_from_bytes(bytes, endianness)?;
+        let value = char::from_u32(code).ok_or(TryFromBytesError::InvalidChar(code))?;
+        Ok((value, size))
+    }
+}
+
+/// NonZero integers are read as their underlying integer, erroring if it is zero
+macro_rules! nonzero_impls {
+    ($($name:ident($int:ty)),+) => {$(
+        impl TryFromBytes for $name {
+            type Bytes = Vec<u8>;
+            type Error = TryFromBytesError;
+
+            fn try_from_bytes(
+                bytes: Self::Bytes,
+                endianness: Endianness,
+            ) -> Result<(Self, usize), Self::Error> {
+                let (value, size) = <$int>::try_from_bytes(bytes, endianness)?;
+                Ok(($name::new(value).ok_or(TryFromBytesError::ZeroValue)?, size))
+            }
+        }
+
+        impl ToBytes for $name {
+            type Bytes = Vec<u8>;
+
+            fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+                self.get().to_bytes(endianness)
+            }
+        }
+    )+};
+}
+
+nonzero_impls!(
+    NonZeroU8(u8),
+    NonZeroU16(u16),
+    NonZeroU32(u32),
+    NonZeroU64(u64),
+    NonZeroU128(u128),
+    NonZeroI8(i8),
+    NonZeroI16(i16),
+    NonZeroI32(i32),
+    NonZeroI64(i64),
+    NonZeroI128(i128)
+);
+
+/// Decodes the element at `index` of a compound value from `bytes[*offset..]`,
+/// advancing `offset` past it
+fn try_element_from_bytes<T>(
+    bytes: &[u8],
+    offset: &mut usize,
+    index: usize,
+    endianness: Endianness,
+) -> Result<T, TryFromBytesError>
+where
+    T: TryFromBytes<Bytes: for<'a> From<&'a [u8]>, Error = TryFromBytesError>,
+{
+    let rest = &bytes[(*offset).min(bytes.len())..];
+    let (value, size) =
+        T::try_from_bytes(rest.into(), endianness).map_err(|error| TryFromBytesError::Element {
+            index,
+            offset: *offset,
+            error: Box::new(error),
+        })?;
+    *offset += size;
+    Ok(value)
+}
+
+/// Arrays are read element by element, each in the given endianness
+impl<T, const N: usize> TryFromBytes for [T; N]
+where
+    T: TryFromBytes<Bytes: for<'a> From<&'a [u8]>, Error = TryFromBytesError>,
+{
+    type Bytes = Vec<u8>;
+    type Error = TryFromBytesError;
+
+    const SIZE: usize = N * T::SIZE;
+    const VARIABLE_SIZE: bool = T::VARIABLE_SIZE;
+
+    fn try_from_bytes(
+        bytes: Self::Bytes,
+        endianness: Endianness,
+    ) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+        let values = (0..N)
+            .map(|index| try_element_from_bytes(&bytes, &mut offset, index, endianness))
+            .collect::<Result<Vec<T>, _>>()?;
+        let array = values
+            .try_into()
+            .or(Err(TryFromBytesError::ArrayFromSlice))?;
+        Ok((array, offset))
+    }
+}
+
+/// Tuples are read in order, each element in the given endianness
+macro_rules! tuple_impls {
+    ($($name:ident $index:tt),+) => {
+        impl<$($name),+> !TNum for ($($name,)+) {}
+
+        impl<$($name),+> TryFromBytes for ($($name,)+)
+        where
+            $($name: TryFromBytes<Bytes: for<'a> From<&'a [u8]>, Error = TryFromBytesError>,)+
+        {
+            type Bytes = Vec<u8>;
+            type Error = TryFromBytesError;
+
+            const SIZE: usize = 0 $(+ $name::SIZE)+;
+            const VARIABLE_SIZE: bool = false $(|| $name::VARIABLE_SIZE)+;
+
+            fn try_from_bytes(
+                bytes: Self::Bytes,
+                endianness: Endianness,
+            ) -> Result<(Self, usize), Self::Error> {
+                let mut offset = 0;
+                let value = ($(
+                    try_element_from_bytes::<$name>(&bytes, &mut offset, $index, endianness)?,
+                )+);
+                Ok((value, offset))
+            }
+        }
+
+        impl<$($name: ToBytes<Bytes = Vec<u8>>),+> ToBytes for ($($name,)+) {
+            type Bytes = Vec<u8>;
+
+            fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+                let mut bytes = Vec::new();
+                $(bytes.append(&mut self.$index.to_bytes(endianness));)+
+                bytes
+            }
+        }
+    };
+}
+
+tuple_impls!(A 0);
+tuple_impls!(A 0, B 1);
+tuple_impls!(A 0, B 1, C 2);
+tuple_impls!(A 0, B 1, C 2, D 3);
+tuple_impls!(A 0, B 1, C 2, D 3, E 4);
+tuple_impls!(A 0, B 1, C 2, D 3, E 4, F 5);
+tuple_impls!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple_impls!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+tuple_impls!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+tuple_impls!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+tuple_impls!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+tuple_impls!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+impl<T: num_traits::FromBytes<Bytes = [u8; size_of::<T>()]> + TNum> TryFromBytes for T
 where
     <T as num_traits::FromBytes>::Bytes: Sized + for<'a> TryFrom<&'a [u8]>,
 {
@@ -100,12 +513,16 @@ where
         bytes: <Self as TryFromBytes>::Bytes,
         endianness: Endianness,
     ) -> Result<(Self, usize), Self::Error> {
+        Self::try_from_slice(&bytes, endianness)
+    }
+
+    fn try_from_slice(bytes: &[u8], endianness: Endianness) -> Result<(Self, usize), Self::Error> {
         let size = size_of::<T>();
         if bytes.len() < size {
             return Err(TryFromBytesError::OutOfBounds);
         }
 
-        let array_bytes: &[u8; size_of::<T>()] = &bytes.as_slice()[..size_of::<T>()]
+        let array_bytes: &[u8; size_of::<T>()] = &bytes[..size_of::<T>()]
             .try_into()
             .or(Err(TryFromBytesError::ArrayFromSlice))?;
         Ok((
@@ -118,7 +535,7 @@ where
     }
 }
 
-impl<T: num_traits::ToBytes + TInt> ToBytes for T
+impl<T: num_traits::ToBytes + TNum> ToBytes for T
 where
     <T as num_traits::ToBytes>::Bytes: Sized + Into<Vec<u8>>,
 {
@@ -132,35 +549,73 @@ where
     }
 }
 
-impl ToBytes for String {
+impl ToBytes for bool {
     type Bytes = Vec<u8>;
 
     fn to_bytes(&self, _: Endianness) -> Self::Bytes {
-        (self.to_owned() + "\0").to_owned().into()
+        vec![*self as u8]
     }
 }
 
-#[derive(Debug)]
-pub enum ByteError {
-    ByteReaderError(ByteReaderError),
-    ByteWriterError(ByteWriterError),
-    IOError(io::Error),
+impl ToBytes for char {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+        (*self as u32).to_bytes(endianness)
+    }
 }
 
-impl From<ByteReaderError> for ByteError {
-    fn from(err: ByteReaderError) -> Self {
-        Self::ByteReaderError(err)
+impl<T: ToBytes<Bytes = Vec<u8>>, const N: usize> ToBytes for [T; N] {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+        self.iter().flat_map(|v| v.to_bytes(endianness)).collect()
     }
 }
 
-impl From<ByteWriterError> for ByteError {
-    fn from(err: ByteWriterError) -> Self {
-        Self::ByteWriterError(err)
+impl ToBytes for String {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+        self.as_str().to_bytes(endianness)
     }
 }
 
-impl From<io::Error> for ByteError {
-    fn from(err: io::Error) -> Self {
-        Self::IOError(err)
+/// Strings are written NUL-terminated, matching how String is read
+impl ToBytes for &str {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, _: Endianness) -> Self::Bytes {
+        let mut bytes = Vec::with_capacity(self.len() + 1);
+        bytes.extend_from_slice(self.as_bytes());
+        bytes.push(0x00);
+        bytes
+    }
+}
+
+impl ToBytes for Cow<'_, str> {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, endianness: Endianness) -> Self::Bytes {
+        self.as_ref().to_bytes(endianness)
+    }
+}
+
+/// Byte blobs are written as they are, without a terminator or length prefix, so unlike
+/// strings they can't be read back on their own
+impl ToBytes for &[u8] {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, _: Endianness) -> Self::Bytes {
+        self.to_vec()
+    }
+}
+
+/// Written as raw bytes, like `&[u8]`
+impl ToBytes for Vec<u8> {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, _: Endianness) -> Self::Bytes {
+        self.clone()
     }
 }
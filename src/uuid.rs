@@ -0,0 +1,134 @@
+//! uuid.rs
+use std::{fmt, io::BufRead};
+
+use super::{ByteReader, ByteWriter, Endianness, Error, ToBytes, TryFromBytes, TryFromBytesError};
+
+/// A 16 byte universally unique identifier, stored as its bytes in order
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Uuid(pub [u8; 16]);
+
+impl Uuid {
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Converts between the canonical and Windows GUID layouts by reversing the first three fields
+    fn swap_guid_fields(self) -> Self {
+        let mut bytes = self.0;
+        bytes[..4].reverse();
+        bytes[4..6].reverse();
+        bytes[6..8].reverse();
+        Self(bytes)
+    }
+}
+
+/// Formats as the canonical hyphenated form, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                write!(f, "-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// UUIDs are always 16 bytes in order, regardless of endianness
+impl TryFromBytes for Uuid {
+    type Bytes = Vec<u8>;
+    type Error = TryFromBytesError;
+
+    fn try_from_bytes(bytes: Self::Bytes, _: Endianness) -> Result<(Self, usize), Self::Error> {
+        let bytes: [u8; 16] = bytes
+            .get(..16)
+            .ok_or(TryFromBytesError::OutOfBounds)?
+            .try_into()
+            .or(Err(TryFromBytesError::ArrayFromSlice))?;
+        Ok((Self(bytes), 16))
+    }
+}
+
+impl ToBytes for Uuid {
+    type Bytes = Vec<u8>;
+
+    fn to_bytes(&self, _: Endianness) -> Self::Bytes {
+        self.0.to_vec()
+    }
+}
+
+impl<'a> ByteReader<'a> {
+    /// Reads a 16 byte UUID
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [
+    ///         0x67, 0xE5, 0x50, 0x44, 0x10, 0xB1, 0x42, 0x6F,
+    ///         0x92, 0x47, 0xBB, 0x68, 0x0E, 0x5F, 0xE0, 0xC8,
+    ///     ];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Big);
+    ///
+    ///     let uuid = reader.read_uuid()?;
+    ///     assert_eq!(uuid.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_uuid(&mut self) -> Result<Uuid, Error> {
+        let bytes = self.peek_bytes::<16>()?;
+        self.consume(16);
+        Ok(Uuid(bytes))
+    }
+
+    /// Reads a Windows GUID, whose first three fields are stored little endian
+    ///
+    /// This is the layout of GUIDs in PE files, the registry and COM. Unlike `read_uuid`,
+    /// the bytes are reordered so the Uuid matches the GUID's canonical string form.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [
+    ///         0x68, 0xE9, 0x36, 0x4D, 0x25, 0xE3, 0xCE, 0x11,
+    ///         0xBF, 0xC1, 0x08, 0x00, 0x2B, 0xE1, 0x03, 0x18,
+    ///     ];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Big);
+    ///
+    ///     let guid = reader.read_guid_le()?;
+    ///     assert_eq!(guid.to_string(), "4d36e968-e325-11ce-bfc1-08002be10318");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_guid_le(&mut self) -> Result<Uuid, Error> {
+        Ok(self.read_uuid()?.swap_guid_fields())
+    }
+}
+
+impl ByteWriter {
+    /// Appends a 16 byte UUID, returning the bytes written
+    pub fn append_uuid(&mut self, data: Uuid) -> usize {
+        self.append(data)
+    }
+
+    /// Appends a Windows GUID with its first three fields little endian, returning the bytes written
+    ///
+    /// This is the inverse of `ByteReader::read_guid_le`.
+    pub fn append_guid_le(&mut self, data: Uuid) -> usize {
+        self.append(data.swap_guid_fields())
+    }
+}
@@ -0,0 +1,267 @@
+//! varint.rs
+use std::io::BufRead;
+
+use super::{ByteReader, ByteWriter, Error, ErrorKind, WriteErrorKind};
+
+/// The largest value a 4 byte variable length quantity can hold
+pub const VLQ_MAX: u32 = 0x0FFF_FFFF;
+
+/// Unsigned integers which can be read as a variable length integer
+pub trait Varint: Copy {
+    const BITS: u32;
+
+    fn from_u64(value: u64) -> Self;
+    fn to_u64(self) -> u64;
+}
+
+macro_rules! varint_impls {
+    ($($t:ty),+) => {$(
+        impl Varint for $t {
+            const BITS: u32 = <$t>::BITS;
+
+            fn from_u64(value: u64) -> Self {
+                value as $t
+            }
+
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+        }
+    )+};
+}
+
+varint_impls!(u8, u16, u32, u64);
+
+/// Signed integers which can be read as a zigzag encoded variable length integer
+pub trait ZigzagVarint: Copy {
+    type Unsigned: Varint;
+
+    fn from_zigzag(value: Self::Unsigned) -> Self;
+    fn to_zigzag(self) -> Self::Unsigned;
+}
+
+macro_rules! zigzag_impls {
+    ($($t:ty: $u:ty),+) => {$(
+        impl ZigzagVarint for $t {
+            type Unsigned = $u;
+
+            fn from_zigzag(value: $u) -> Self {
+                (value >> 1) as $t ^ -((value & 1) as $t)
+            }
+
+            fn to_zigzag(self) -> $u {
+                ((self << 1) ^ (self >> (<$t>::BITS - 1))) as $u
+            }
+        }
+    )+};
+}
+
+zigzag_impls!(i8: u8, i16: u16, i32: u32, i64: u64);
+
+impl<'a> ByteReader<'a> {
+    /// Reads an unsigned LEB128 encoded integer
+    ///
+    /// Errors with `VarintOverflow` if the encoding is longer than T allows or holds
+    /// bits that don't fit in T, and `UnexpectedEof` if the buffer ends mid-value. The
+    /// cursor is left untouched on failure.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0xE5, 0x8E, 0x26];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_varint::<u32>()?, 624485);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_varint<T: Varint>(&mut self) -> Result<T, Error> {
+        let (value, size) = self.peek_leb128::<T>()?;
+        self.consume(size);
+        Ok(T::from_u64(value))
+    }
+
+    /// Reads a zigzag encoded LEB128 integer, as used by protobuf's sint fields
+    ///
+    /// Errors in the same way as `read_varint`.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x01, 0x03, 0x04];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_varint_zigzag::<i32>()?, -1);
+    ///     assert_eq!(reader.read_varint_zigzag::<i32>()?, -2);
+    ///     assert_eq!(reader.read_varint_zigzag::<i32>()?, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_varint_zigzag<T: ZigzagVarint>(&mut self) -> Result<T, Error> {
+        Ok(T::from_zigzag(self.read_varint::<T::Unsigned>()?))
+    }
+
+    /// Reads a MIDI style variable length quantity: 7 bits per byte, most significant
+    /// group first, with the high bit set on every byte but the last
+    ///
+    /// Errors with `VarintOverflow` if the quantity is longer than 4 bytes and
+    /// `UnexpectedEof` if the buffer ends mid-value, leaving the cursor untouched.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = [0x81, 0x80, 0x00];
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_vlq()?, 0x4000);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_vlq(&mut self) -> Result<u32, Error> {
+        let mut value = 0u32;
+        for (i, &byte) in self.cursor.iter().enumerate() {
+            if i == 4 {
+                return Err(self.err(ErrorKind::VarintOverflow));
+            }
+            value = (value << 7) | (byte & 0x7F) as u32;
+            if byte & 0x80 == 0 {
+                self.consume(i + 1);
+                return Ok(value);
+            }
+        }
+        Err(self.eof_err::<u32>(self.len() + 1, self.len()))
+    }
+
+    /// Reads a .NET style 7 bit encoded int, as written by BinaryWriter.Write7BitEncodedInt
+    ///
+    /// This is a LEB128 u32, so errors with `VarintOverflow` on encodings longer than 5
+    /// bytes and `UnexpectedEof` if the buffer ends mid-value.
+    pub fn read_7bit_encoded_int(&mut self) -> Result<u32, Error> {
+        self.read_varint::<u32>()
+    }
+
+    /// Reads a .NET BinaryWriter string: a 7 bit encoded length then that many UTF-8 bytes
+    ///
+    /// The cursor is left untouched if the string is truncated or isn't valid UTF-8.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(generic_const_exprs)]
+    ///
+    /// use bitchomp::{ByteReader, Endianness, Error};
+    ///
+    /// fn main() -> Result<(), Error> {
+    ///     let buf = *b"\x05hello";
+    ///     let mut reader = ByteReader::new(&buf, Endianness::Little);
+    ///
+    ///     assert_eq!(reader.read_dotnet_string()?, "hello");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_dotnet_string(&mut self) -> Result<String, Error> {
+        let (len, size) = self.peek_leb128::<u32>()?;
+        let len = len as usize;
+        if self.len() - size < len {
+            return Err(self.eof_err::<String>(size + len, self.len()));
+        }
+        let value = self.decode_utf8(&self.cursor[size..size + len], size)?;
+        self.consume(size + len);
+        Ok(value)
+    }
+
+    /// Decodes an unsigned LEB128 value that fits in T, returning it and its encoded size
+    fn peek_leb128<T: Varint>(&self) -> Result<(u64, usize), Error> {
+        let bits = T::BITS;
+        let max_len = bits.div_ceil(7) as usize;
+        let mut value = 0u64;
+        for (i, &byte) in self.cursor.iter().enumerate() {
+            let shift = i as u32 * 7;
+            let payload = (byte & 0x7F) as u64;
+            if i == max_len || (shift + 7 > bits && payload >> (bits - shift) != 0) {
+                return Err(self.err(ErrorKind::VarintOverflow));
+            }
+            value |= payload << shift;
+            if byte & 0x80 == 0 {
+                return Ok((value, i + 1));
+            }
+        }
+        Err(self.eof_err::<T>(self.len() + 1, self.len()))
+    }
+}
+
+impl ByteWriter {
+    /// Appends an unsigned LEB128 encoded integer, returning the bytes written
+    pub fn append_varint<T: Varint>(&mut self, data: T) -> usize {
+        self.append_leb128(data.to_u64())
+    }
+
+    /// Appends a zigzag encoded LEB128 integer, returning the bytes written
+    pub fn append_varint_zigzag<T: ZigzagVarint>(&mut self, data: T) -> usize {
+        self.append_varint(data.to_zigzag())
+    }
+
+    /// Appends a MIDI style variable length quantity, returning the bytes written
+    ///
+    /// Errors if `data` is larger than `VLQ_MAX`, which doesn't fit in 4 bytes.
+    pub fn write_vlq(&mut self, data: u32) -> Result<usize, Error> {
+        if data > VLQ_MAX {
+            return Err(self.err(WriteErrorKind::VarintOverflow { value: data as u64 }));
+        }
+        let mut bytes = vec![(data & 0x7F) as u8];
+        let mut value = data >> 7;
+        while value != 0 {
+            bytes.insert(0, (value & 0x7F) as u8 | 0x80);
+            value >>= 7;
+        }
+        let size = bytes.len();
+        self.append_vec(bytes);
+        Ok(size)
+    }
+
+    /// Appends a .NET style 7 bit encoded int, returning the bytes written
+    pub fn write_7bit_encoded_int(&mut self, data: u32) -> usize {
+        self.append_varint(data)
+    }
+
+    /// Appends a .NET BinaryWriter string, prefixed by its length as a 7 bit encoded int,
+    /// returning the bytes written
+    pub fn append_dotnet_string(&mut self, data: &str) -> Result<usize, Error> {
+        let len = u32::try_from(data.len())
+            .map_err(|_| self.err(WriteErrorKind::LengthOverflow { len: data.len() }))?;
+        let size = self.write_7bit_encoded_int(len);
+        self.append_vec(data.as_bytes().to_vec());
+        Ok(size + data.len())
+    }
+
+    fn append_leb128(&mut self, mut value: u64) -> usize {
+        let mut bytes = Vec::new();
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                break;
+            }
+            bytes.push(byte | 0x80);
+        }
+        let size = bytes.len();
+        self.append_vec(bytes);
+        size
+    }
+}
@@ -0,0 +1,23 @@
+//! backtrace.rs
+//!
+//! Backtraces check the environment once per process, so this has its own test binary
+//! rather than living with the unit tests.
+#![cfg(feature = "backtrace")]
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+
+use std::backtrace::BacktraceStatus;
+
+use bitchomp::{ByteReader, Endianness};
+
+#[test]
+fn test_backtrace() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    let err = ByteReader::new(&[0x01], Endianness::Little)
+        .read_value::<u32>()
+        .unwrap_err();
+    assert_eq!(err.backtrace().status(), BacktraceStatus::Captured);
+    assert!(format!("{err:#?}").contains("backtrace"));
+    assert!(!format!("{err:?}").contains("backtrace"));
+}